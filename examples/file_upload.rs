@@ -7,15 +7,21 @@ use lab01_2022_input_validation::*;
 use lazy_static::lazy_static;
 use read_input::prelude::*;
 use std::collections::HashMap;
-use std::fs;
 use std::sync::Mutex;
-use uuid::Uuid;
+
+// directory from which the files can be uploaded
+const UPLOAD_ROOT: &str = ".";
+// maximum sizes of the uploaded files, in bytes. Bigger files are refused before being read.
+const MAX_IMAGE_SIZE: u64 = 10 * 1024 * 1024;
+const MAX_VIDEO_SIZE: u64 = 100 * 1024 * 1024;
 
 // store the content we need from a file.
 #[derive(Clone)]
 struct MediaFile {
     path: String,
-    media_type: String,
+    // storage name built from the UUID and the detected extension, the name given by the user is never exposed
+    name: String,
+    media_type: MediaKind,
 }
 
 // Hashmap that store the saved relation UUID -> MediaFile
@@ -25,7 +31,8 @@ lazy_static! {
 
 // Function that upload a MediaFile with an associated UUID.
 // Return : True if the upload is successuful. False if the file already exist, and the content is not uploaded/modified.
-fn upload_file(uuid: &String, filepath: &String, media_type: String) -> bool {
+#[allow(clippy::ptr_arg)]
+fn upload_file(uuid: &String, filepath: &String, name: String, media_type: MediaKind) -> bool {
     let mut map = HASHMAP.lock().unwrap();
     match map.get(uuid) {
         Some(_) => false, // already is in the Hashmap
@@ -34,7 +41,8 @@ fn upload_file(uuid: &String, filepath: &String, media_type: String) -> bool {
                 uuid.clone(),
                 MediaFile {
                     path: filepath.clone(),
-                    media_type: media_type.clone(),
+                    name,
+                    media_type,
                 },
             );
             true
@@ -46,32 +54,72 @@ fn upload_file(uuid: &String, filepath: &String, media_type: String) -> bool {
 // WARNING : Please modify these output for your own use case. This solution can leak some info. This is an example file upload case.
 // The UUID is based on the file content. It include that a file can not be uploaded twice, even with 2 different names.
 fn file_upload_handler() {
+    // the structure of the file is verified and polyglot files are refused
+    // only the files of the working directory can be uploaded, the path given by the user can't escape it
+    let validator = match SandboxedFileValidator::new(
+        UPLOAD_ROOT,
+        FileValidator::new()
+            .deep_check(true)
+            .reject_polyglots(true)
+            .max_size_for(MediaKind::Image, MAX_IMAGE_SIZE)
+            .max_size_for(MediaKind::Video, MAX_VIDEO_SIZE),
+    ) {
+        Ok(validator) => validator,
+        Err(_) => {
+            println!("The upload directory is not available");
+            return;
+        }
+    };
     loop {
         let filepath = input::<String>()
             .msg("Please enter the path to an image or video file : ")
             .get();
-        match validate_file(&filepath, true) {
-            Ok(media_type_opt) => {
-                //The selected File exist
-                match media_type_opt {
-                    Some(media_type) => {
-                        // The selected file is valid and we retrieve his media type ("video" or "image")
-                        // we get the buffer to calculate the uuid with the file content with the default UUID v5 namespace (deterministic)
-                        let buffer = &fs::read(&filepath).unwrap();
-                        let uuid = Uuid::new_v5(&Uuid::default(), buffer)
-                            .as_hyphenated()
-                            .to_string();
-                        if upload_file(&uuid, &filepath, media_type.to_string()) {
-                            println!("File uploaded successfully, UUID : {}", uuid);
-                            break; // correct input, we leave the loop
-                        } else {
-                            println!("This file already exists")
-                        }
+        // the file is opened once, the UUID is computed from the bytes that have been verified
+        match validator.validate_and_read(&filepath) {
+            Ok(validated) => {
+                // the metadata of the images (location, device, ...) are removed, only the stripped copy is stored
+                let validated = match validated.strip_metadata() {
+                    Ok(stripped) => stripped,
+                    Err(_) => {
+                        println!("Invalid file content");
+                        continue;
                     }
-                    None => println!("{}", "Invalid file content"),
+                };
+                // The selected file is valid and we retrieve his media type (video or image)
+                // the uuid is based on the stored content with the default UUID v5 namespace (deterministic)
+                let uuid = validated.uuid().to_string();
+                let media_info = validated.info();
+                let Some(name) = storage_name(&uuid, media_info) else {
+                    println!("The file can't be stored");
+                    continue;
+                };
+                let storage_path = std::env::temp_dir().join(&name);
+                if validated.persist(&storage_path).is_err() {
+                    println!("The file can't be stored");
+                    continue;
                 }
+                if upload_file(
+                    &uuid,
+                    &storage_path.to_string_lossy().to_string(),
+                    name,
+                    media_info.kind,
+                ) {
+                    println!("File uploaded successfully, UUID : {}", uuid);
+                    break; // correct input, we leave the loop
+                } else {
+                    println!("This file already exists")
+                }
+            }
+            Err(FileValidationError::Unreadable(_)) => {
+                println!("The file is not readable or doesn't exist")
             }
-            Err(_) => println!("{}", "The file is not readable or doesn't exist"),
+            Err(FileValidationError::TooLarge { max, .. }) => {
+                println!("The file is too large, the maximum is {} bytes", max)
+            }
+            Err(FileValidationError::OutsideRoot(_)) => {
+                println!("The file must be in the upload directory")
+            }
+            Err(_) => println!("Invalid file content"),
         }
     }
 }
@@ -79,10 +127,7 @@ fn file_upload_handler() {
 // Allow to retrieve a copy of the MediaFile in the Hashmap with the uuid key. Returh None if non-existant
 fn retrieve_with_uuid(uuid: &String) -> Option<MediaFile> {
     let map = HASHMAP.lock().unwrap();
-    match map.get(uuid) {
-        Some(media_file) => Some(media_file.clone()),
-        None => None,
-    }
+    map.get(uuid).cloned()
 }
 
 // Function that allow the verification of the existance of the file in the Hashmap
@@ -130,7 +175,7 @@ fn get_url_handler() {
         .msg("Please enter the UUID to get : ")
         .get();
     if let Some(media_file) = retrieve_with_uuid(&uuid_input) {
-        println!("sec.upload/{}s/{}", media_file.media_type, media_file.path)
+        println!("sec.upload/{}s/{}", media_file.media_type, media_file.name)
     } else {
         // we only give the File not found info to user there. More info could leak something.
         println!("File not found")
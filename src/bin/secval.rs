@@ -0,0 +1,508 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Command line tool that applies the validators of the library to files, URLs and UUIDs, for the shell scripts and the git hooks.
+
+use lab01_2022_input_validation::{
+    content_uuid, validate_file_detailed, validate_file_with_uuid, validate_uuid, UrlPolicy,
+    MAX_UUID_FILE_SIZE,
+};
+use std::fs::File;
+use std::io::{self, BufRead, Read};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: secval [--json] <command> [options] [inputs...]
+
+Commands:
+  file [--no-extension] <path>...   verify that the files are images or videos whose extension matches the content
+  url [--scheme <scheme>]... [--tld <.tld>]... <url>...
+                                    verify the URLs, only with the given schemes (http and https by default)
+                                    and only in the given top level domains if any
+  uuid <uuid>...                    verify the format of the UUIDs
+  hash <uuid> <path>...             verify that each file has the UUID given before it
+  hash --compute <path>...          print the UUID of each file, in the input format of hash
+
+Without input, or with the input '-', the inputs are read from the standard input, one per line
+(\"<uuid> <path>\" for hash).
+
+Options:
+  --json        print the results as a JSON document
+  -h, --help    print this help
+
+Exit status: 0 if every input is valid, 1 if an input is invalid, 2 if the arguments are invalid.";
+
+// exit status when the arguments can't be parsed
+const USAGE_ERROR: u8 = 2;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    File {
+        verify_extension: bool,
+    },
+    Url {
+        schemes: Vec<String>,
+        whitelist: Vec<String>,
+    },
+    Uuid,
+    Hash {
+        compute: bool,
+    },
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::File { .. } => "file",
+            Command::Url { .. } => "url",
+            Command::Uuid => "uuid",
+            Command::Hash { .. } => "hash",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Arguments {
+    json: bool,
+    command: Command,
+    // inputs given on the command line, "-" for the standard input
+    inputs: Vec<String>,
+}
+
+// Result of the verification of an input. details are printed after the input, e.g. ("mime_type", "image/png").
+struct Outcome {
+    input: String,
+    valid: bool,
+    details: Vec<(&'static str, String)>,
+}
+
+impl Outcome {
+    fn valid(input: &str, details: Vec<(&'static str, String)>) -> Self {
+        Outcome {
+            input: input.to_string(),
+            valid: true,
+            details,
+        }
+    }
+
+    fn invalid(input: &str, error: String) -> Self {
+        Outcome {
+            input: input.to_string(),
+            valid: false,
+            details: vec![("error", error)],
+        }
+    }
+}
+
+// Parse the arguments, without the name of the program. Return Ok(None) if the help is asked.
+fn parse_arguments<I: IntoIterator<Item = String>>(
+    arguments: I,
+) -> Result<Option<Arguments>, String> {
+    let mut json = false;
+    let mut command: Option<Command> = None;
+    let mut inputs = Vec::new();
+    let mut options_ended = false;
+    let mut arguments = arguments.into_iter();
+    while let Some(argument) = arguments.next() {
+        if options_ended || argument == "-" || !argument.starts_with('-') {
+            match command {
+                None => {
+                    command = Some(match argument.as_str() {
+                        "file" => Command::File {
+                            verify_extension: true,
+                        },
+                        "url" => Command::Url {
+                            schemes: Vec::new(),
+                            whitelist: Vec::new(),
+                        },
+                        "uuid" => Command::Uuid,
+                        "hash" => Command::Hash { compute: false },
+                        _ => return Err(format!("unknown command '{}'", argument)),
+                    })
+                }
+                Some(_) => inputs.push(argument),
+            }
+            continue;
+        }
+        match (argument.as_str(), command.as_mut()) {
+            ("-h" | "--help", _) => return Ok(None),
+            ("--json", _) => json = true,
+            ("--", Some(_)) => options_ended = true,
+            ("--no-extension", Some(Command::File { verify_extension })) => {
+                *verify_extension = false
+            }
+            ("--scheme", Some(Command::Url { schemes, .. })) => schemes.push(
+                arguments
+                    .next()
+                    .ok_or_else(|| "option '--scheme' needs a value".to_string())?,
+            ),
+            ("--tld", Some(Command::Url { whitelist, .. })) => whitelist.push(
+                arguments
+                    .next()
+                    .ok_or_else(|| "option '--tld' needs a value".to_string())?,
+            ),
+            ("--compute", Some(Command::Hash { compute })) => *compute = true,
+            _ => return Err(format!("unknown option '{}'", argument)),
+        }
+    }
+    let command = command.ok_or_else(|| "a command is needed".to_string())?;
+    if command == (Command::Hash { compute: false })
+        && inputs.iter().filter(|input| *input != "-").count() % 2 != 0
+    {
+        return Err("hash needs a path after each UUID".to_string());
+    }
+    Ok(Some(Arguments {
+        json,
+        command,
+        inputs,
+    }))
+}
+
+// The inputs of the command line, each "-" being replaced by the lines of the reader. Without input, the lines of the reader are used.
+// The UUIDs and paths given to hash on the command line are joined in "<uuid> <path>" lines.
+fn collect_inputs<R: BufRead>(arguments: &Arguments, reader: R) -> io::Result<Vec<String>> {
+    let mut lines = Some(reader.lines());
+    let mut read_lines = |inputs: &mut Vec<String>| -> io::Result<()> {
+        // the standard input is read only once, even if "-" is given several times
+        for line in lines.take().into_iter().flatten() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                inputs.push(line.trim_end_matches('\r').to_string());
+            }
+        }
+        Ok(())
+    };
+    let mut inputs = Vec::new();
+    if arguments.inputs.is_empty() {
+        read_lines(&mut inputs)?;
+        return Ok(inputs);
+    }
+    let mut pending_uuid: Option<&String> = None;
+    for argument in &arguments.inputs {
+        if argument == "-" {
+            read_lines(&mut inputs)?;
+        } else if arguments.command == (Command::Hash { compute: false }) {
+            match pending_uuid.take() {
+                Some(uuid) => inputs.push(format!("{} {}", uuid, argument)),
+                None => pending_uuid = Some(argument),
+            }
+        } else {
+            inputs.push(argument.clone());
+        }
+    }
+    Ok(inputs)
+}
+
+// Verify an input with the command.
+fn run(command: &Command, input: &str) -> Outcome {
+    match command {
+        Command::File { verify_extension } => {
+            match validate_file_detailed(input, *verify_extension) {
+                Ok(info) => Outcome::valid(
+                    input,
+                    vec![
+                        ("kind", info.kind.to_string()),
+                        ("mime_type", info.mime_type.to_string()),
+                    ],
+                ),
+                Err(e) => Outcome::invalid(input, e.to_string()),
+            }
+        }
+        Command::Url { schemes, whitelist } => {
+            let whitelist: Vec<&str> = whitelist.iter().map(String::as_str).collect();
+            let mut policy = UrlPolicy::new().top_domains(&whitelist);
+            // an empty list would accept every scheme, the default ones are kept without --scheme
+            if !schemes.is_empty() {
+                let schemes: Vec<&str> = schemes.iter().map(String::as_str).collect();
+                policy = policy.schemes(&schemes);
+            }
+            match policy.validate(input) {
+                Ok(_) => Outcome::valid(input, Vec::new()),
+                Err(e) => Outcome::invalid(input, e.to_string()),
+            }
+        }
+        Command::Uuid => match validate_uuid(input) {
+            true => Outcome::valid(input, Vec::new()),
+            false => Outcome::invalid(input, "invalid UUID".to_string()),
+        },
+        Command::Hash { compute: true } => match file_uuid(input) {
+            Ok(uuid) => Outcome::valid(input, vec![("uuid", uuid)]),
+            Err(e) => Outcome::invalid(input, e.to_string()),
+        },
+        Command::Hash { compute: false } => {
+            let (uuid, path) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+            let path = path.trim_start();
+            let outcome = if !validate_uuid(uuid) {
+                Outcome::invalid(path, "invalid UUID".to_string())
+            } else {
+                match validate_file_with_uuid(uuid, path) {
+                    Ok(true) => Outcome::valid(path, Vec::new()),
+                    Ok(false) => {
+                        Outcome::invalid(path, "content doesn't match the UUID".to_string())
+                    }
+                    Err(e) => Outcome::invalid(path, e.to_string()),
+                }
+            };
+            Outcome {
+                details: [vec![("uuid", uuid.to_string())], outcome.details].concat(),
+                ..outcome
+            }
+        }
+    }
+}
+
+// the UUID of the content of the file, read with the same limit as validate_file_with_uuid
+fn file_uuid(path: &str) -> io::Result<String> {
+    let file = File::open(path)?;
+    let mut buffer = Vec::new();
+    file.take(MAX_UUID_FILE_SIZE.saturating_add(1))
+        .read_to_end(&mut buffer)?;
+    if buffer.len() as u64 > MAX_UUID_FILE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "file exceeds the maximum size",
+        ));
+    }
+    Ok(content_uuid(&buffer))
+}
+
+// a JSON string, with the quotes, the backslashes and the control characters escaped
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// {"command": "file", "valid": false, "results": [{"input": "a.png", "valid": true, "kind": "image", ...}, ...]}
+fn to_json(command: &Command, outcomes: &[Outcome]) -> String {
+    let results: Vec<String> = outcomes
+        .iter()
+        .map(|outcome| {
+            let mut fields = vec![
+                format!("\"input\": {}", json_string(&outcome.input)),
+                format!("\"valid\": {}", outcome.valid),
+            ];
+            fields.extend(
+                outcome
+                    .details
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", json_string(key), json_string(value))),
+            );
+            format!("{{{}}}", fields.join(", "))
+        })
+        .collect();
+    format!(
+        "{{\"command\": {}, \"valid\": {}, \"results\": [{}]}}",
+        json_string(command.name()),
+        outcomes.iter().all(|outcome| outcome.valid),
+        results.join(", ")
+    )
+}
+
+// one line per input : the valid ones on the standard output, the invalid ones with their reason on the error output.
+// hash --compute prints "<uuid> <path>", which can be given back to hash.
+fn print_text(command: &Command, outcomes: &[Outcome]) {
+    for outcome in outcomes {
+        let detail = |key| {
+            outcome
+                .details
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.as_str())
+                .unwrap_or_default()
+        };
+        match (outcome.valid, command) {
+            (true, Command::Hash { compute: true }) => {
+                println!("{} {}", detail("uuid"), outcome.input)
+            }
+            (true, Command::File { .. }) => println!(
+                "{}: valid {} ({})",
+                outcome.input,
+                detail("kind"),
+                detail("mime_type")
+            ),
+            (true, _) => println!("{}: valid", outcome.input),
+            (false, _) => eprintln!("{}: invalid, {}", outcome.input, detail("error")),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let arguments = match parse_arguments(std::env::args().skip(1)) {
+        Ok(Some(arguments)) => arguments,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("secval: {}\n\n{}", e, USAGE);
+            return ExitCode::from(USAGE_ERROR);
+        }
+    };
+    let inputs = match collect_inputs(&arguments, io::stdin().lock()) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("secval: standard input is not readable: {}", e);
+            return ExitCode::from(USAGE_ERROR);
+        }
+    };
+    let outcomes: Vec<Outcome> = inputs
+        .iter()
+        .map(|input| run(&arguments.command, input))
+        .collect();
+    if arguments.json {
+        println!("{}", to_json(&arguments.command, &outcomes));
+    } else {
+        print_text(&arguments.command, &outcomes);
+    }
+    match outcomes.iter().all(|outcome| outcome.valid) {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{collect_inputs, json_string, parse_arguments, run, to_json, Arguments, Command};
+    use lab01_2022_input_validation::content_uuid;
+    use std::fs;
+
+    fn parse(arguments: &[&str]) -> Result<Option<Arguments>, String> {
+        parse_arguments(arguments.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn arguments() {
+        assert_eq!(
+            parse(&[
+                "--json", "url", "--tld", ".ch", "a.ch", "-", "--tld", ".com", "--scheme", "ftp"
+            ]),
+            Ok(Some(Arguments {
+                json: true,
+                command: Command::Url {
+                    schemes: vec!["ftp".to_string()],
+                    whitelist: vec![".ch".to_string(), ".com".to_string()]
+                },
+                inputs: vec!["a.ch".to_string(), "-".to_string()],
+            }))
+        );
+        assert_eq!(
+            parse(&["file", "--no-extension", "--", "--json"])
+                .unwrap()
+                .unwrap(),
+            Arguments {
+                json: false,
+                command: Command::File {
+                    verify_extension: false
+                },
+                inputs: vec!["--json".to_string()],
+            }
+        );
+        assert_eq!(parse(&["uuid", "--help"]), Ok(None));
+        for invalid in [
+            &[][..],
+            &["--json"],
+            &["zip"],
+            &["uuid", "--tld", ".ch"],
+            &["url", "--tld"],
+            &["url", "--scheme"],
+            &["hash", "only-a-uuid"],
+            &["hash", "uuid", "path", "-", "uuid"],
+            &["file", "--compute"],
+        ] {
+            assert!(
+                parse(invalid).is_err(),
+                "{:?} schould not be accepted",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn standard_input() {
+        let arguments = parse(&["uuid", "first", "-", "last", "-"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            collect_inputs(&arguments, "a\r\n\n  \nb\n".as_bytes()).unwrap(),
+            vec!["first", "a", "b", "last"]
+        );
+        let arguments = parse(&["hash", "uuid1", "path 1", "uuid2", "path2"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            collect_inputs(&arguments, "ignored".as_bytes()).unwrap(),
+            vec!["uuid1 path 1", "uuid2 path2"]
+        );
+        let arguments = parse(&["file"]).unwrap().unwrap();
+        assert_eq!(
+            collect_inputs(&arguments, "res/image/a.png\n".as_bytes()).unwrap(),
+            vec!["res/image/a.png"]
+        );
+    }
+
+    #[test]
+    fn commands() {
+        let png = "res/image/file_example_PNG_500kB.png";
+        let file = Command::File {
+            verify_extension: true,
+        };
+        assert!(run(&file, png).valid);
+        assert!(!run(&file, "res/other_extension/file_example_CSV_5000.csv").valid);
+        let url = Command::Url {
+            schemes: Vec::new(),
+            whitelist: vec![".ch".to_string()],
+        };
+        assert!(run(&url, "https://heig-vd.ch").valid);
+        assert!(!run(&url, "https://heig-vd.com").valid);
+        assert!(!run(&url, "javascript://heig-vd.ch/%0Aalert(1)").valid);
+        assert!(!run(&url, "file://heig-vd.ch/etc/passwd").valid);
+        let ftp = Command::Url {
+            schemes: vec!["ftp".to_string()],
+            whitelist: Vec::new(),
+        };
+        assert!(run(&ftp, "ftp://heig-vd.ch").valid);
+        assert!(!run(&ftp, "https://heig-vd.ch").valid);
+        assert!(run(&Command::Uuid, "4d6e3d4e-8f4b-5c7a-9d1e-2f3a4b5c6d7e").valid);
+        assert!(!run(&Command::Uuid, "not-a-uuid").valid);
+        let uuid = content_uuid(&fs::read(png).unwrap());
+        let computed = run(&Command::Hash { compute: true }, png);
+        assert_eq!(computed.details, vec![("uuid", uuid.clone())]);
+        let check = Command::Hash { compute: false };
+        assert!(run(&check, &format!("{} {}", uuid, png)).valid);
+        assert!(
+            !run(
+                &check,
+                &format!("{}  res/image/file_example_GIF_500kB.gif", uuid)
+            )
+            .valid
+        );
+        assert!(!run(&check, &format!("{} file_that_schould_not_exist", uuid)).valid);
+        assert!(!run(&check, png).valid);
+    }
+
+    #[test]
+    fn json_output() {
+        assert_eq!(json_string("a\"b\\c\n\u{1}é"), "\"a\\\"b\\\\c\\n\\u0001é\"");
+        let outcomes = vec![
+            run(&Command::Uuid, "4d6e3d4e-8f4b-5c7a-9d1e-2f3a4b5c6d7e"),
+            run(&Command::Uuid, "x"),
+        ];
+        assert_eq!(
+            to_json(&Command::Uuid, &outcomes),
+            "{\"command\": \"uuid\", \"valid\": false, \"results\": [\
+             {\"input\": \"4d6e3d4e-8f4b-5c7a-9d1e-2f3a4b5c6d7e\", \"valid\": true}, \
+             {\"input\": \"x\", \"valid\": false, \"error\": \"invalid UUID\"}]}"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Detection of homograph hosts : labels mixing scripts, labels made of confusable characters and hosts that look like a protected domain (UTS #39).
+
+use crate::{idna_forms, parse_url, ParsedUrl, UrlParseError};
+use std::fmt;
+use unicode_security::{skeleton, MixedScript};
+
+// Reason why a host can be mistaken for another one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HomographThreat {
+    // the label mixes several scripts, like "pаypal" with a Cyrillic "а"
+    MixedScript { label: String },
+    // the label is written in a single script but its skeleton is ASCII, like the Cyrillic "аррӏе" confusable with "apple"
+    ConfusableLabel { label: String, skeleton: String },
+    // the host is not the protected domain (or one of its subdomains) but looks like it, like "paypa1.com" for "paypal.com"
+    ProtectedDomain { domain: String },
+}
+
+impl fmt::Display for HomographThreat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HomographThreat::MixedScript { label } => {
+                write!(f, "label \"{}\" mixes several scripts", label)
+            }
+            HomographThreat::ConfusableLabel { label, skeleton } => {
+                write!(f, "label \"{}\" is confusable with \"{}\"", label, skeleton)
+            }
+            HomographThreat::ProtectedDomain { domain } => {
+                write!(f, "host looks like the protected domain {}", domain)
+            }
+        }
+    }
+}
+
+// A protected domain with its A-labels and the skeleton of its U-labels.
+#[derive(Debug, Clone)]
+struct ProtectedDomain {
+    ascii: String,
+    skeleton: String,
+}
+
+// Detection of homograph hosts. Build it with HomographDetector::new() and the builder methods, then call detect(url).
+// By default, the scripts and the confusable characters of each label are verified and no domain is protected.
+#[derive(Debug, Clone, Default)]
+pub struct HomographDetector {
+    protected: Vec<ProtectedDomain>,
+}
+
+impl HomographDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Protect a domain, like "paypal.com" : a host whose skeleton is the one of the domain, or of one of its subdomains,
+    // is reported if it is not the domain or one of its subdomains. Can be called many times.
+    pub fn protect(mut self, domain: &str) -> Self {
+        let domain = domain.strip_suffix('.').unwrap_or(domain);
+        let (ascii, unicode) =
+            idna_forms(domain).unwrap_or_else(|| (domain.to_lowercase(), domain.to_lowercase()));
+        self.protected.push(ProtectedDomain {
+            ascii,
+            skeleton: confusable_skeleton(&unicode),
+        });
+        self
+    }
+
+    // Protect each of the given domains, see protect.
+    pub fn protected_domains(self, domains: &[&str]) -> Self {
+        domains
+            .iter()
+            .fold(self, |detector, domain| detector.protect(domain))
+    }
+
+    // Search the threats in the host of the URL. Return an empty vector if the host is safe or if there is no host.
+    pub fn detect(&self, url: &ParsedUrl) -> Vec<HomographThreat> {
+        let (Some(ascii), Some(unicode)) = (&url.host_ascii, &url.host_unicode) else {
+            return Vec::new();
+        };
+        let mut threats = Vec::new();
+        for label in unicode.split('.').filter(|label| !label.is_ascii()) {
+            if !label.is_single_script() {
+                threats.push(HomographThreat::MixedScript {
+                    label: label.to_string(),
+                });
+                continue;
+            }
+            let skeleton = confusable_skeleton(label);
+            if skeleton.is_ascii() {
+                threats.push(HomographThreat::ConfusableLabel {
+                    label: label.to_string(),
+                    skeleton,
+                });
+            }
+        }
+        // the root dot of a fully qualified host, like "paypa1.com.", is not part of the compared domain
+        let ascii = ascii.strip_suffix('.').unwrap_or(ascii);
+        let host_skeleton = confusable_skeleton(unicode.strip_suffix('.').unwrap_or(unicode));
+        for protected in &self.protected {
+            if !is_same_or_subdomain(&host_skeleton, &protected.skeleton)
+                || is_same_or_subdomain(ascii, &protected.ascii)
+            {
+                continue;
+            }
+            threats.push(HomographThreat::ProtectedDomain {
+                domain: protected.ascii.clone(),
+            });
+        }
+        threats
+    }
+}
+
+// true if host is domain or ends with "." followed by domain
+fn is_same_or_subdomain(host: &str, domain: &str) -> bool {
+    host.strip_suffix(domain)
+        .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with('.'))
+}
+
+// The skeleton of UTS #39 : two texts that can be confused have the same skeleton, like "paypal" and "pаypa1".
+pub fn confusable_skeleton(text: &str) -> String {
+    skeleton(text).collect()
+}
+
+// Parse the URL and search the threats in its host, see HomographDetector.
+// protected : the domains that a host must not look like
+pub fn detect_homograph(
+    url: &str,
+    protected: &[&str],
+) -> Result<Vec<HomographThreat>, UrlParseError> {
+    let parsed = parse_url(url)?;
+    Ok(HomographDetector::new()
+        .protected_domains(protected)
+        .detect(&parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{confusable_skeleton, detect_homograph, HomographThreat};
+
+    const BRANDS: [&str; 2] = ["paypal.com", "heig-vd.ch"];
+
+    #[test]
+    fn safe_hosts() {
+        for url in [
+            "https://paypal.com/login",
+            "https://www.paypal.com",
+            "https://heig-vd.ch",
+            "https://bücher.ch",
+            "https://пример.рф",
+            "https://ελληνικά.gr",
+            "https://例え.jp",
+            "https://xn--bcher-kva.ch",
+        ] {
+            assert_eq!(
+                detect_homograph(url, &BRANDS).unwrap(),
+                Vec::new(),
+                "{} schould be safe",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn mixed_and_confusable_scripts() {
+        assert_eq!(
+            detect_homograph("https://pаypal.org", &[]).unwrap(),
+            vec![HomographThreat::MixedScript {
+                label: "pаypal".to_string()
+            }]
+        );
+        // the punycode form of the Cyrillic "аррӏе"
+        assert_eq!(
+            detect_homograph("https://xn--80ak6aa92e.com", &[]).unwrap(),
+            vec![HomographThreat::ConfusableLabel {
+                label: "аррӏе".to_string(),
+                skeleton: "appie".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn protected_domains() {
+        for (url, threats) in [
+            ("https://paypa1.com", 1),
+            ("https://paypa1.com.", 1),
+            ("https://login.pаypal.com./", 1),
+            ("https://login.paypa1.com/", 1),
+            ("https://PAYPAL.COM.", 0),
+            ("https://pаypal.com", 1),
+            ("https://heig-vd.ch.evil.com", 0),
+            ("https://heiq-vd.ch", 0),
+            ("https://rnyheig-vd.ch", 0),
+        ] {
+            let found = detect_homograph(url, &BRANDS).unwrap();
+            assert_eq!(
+                found
+                    .iter()
+                    .filter(|threat| matches!(threat, HomographThreat::ProtectedDomain { .. }))
+                    .count(),
+                threats,
+                "{} : {:?}",
+                url,
+                found
+            );
+        }
+        assert_eq!(
+            confusable_skeleton("paypa1"),
+            confusable_skeleton("pаypal"),
+            "A digit and a Cyrillic letter schould be confused with Latin letters"
+        );
+        assert_ne!(confusable_skeleton("paypal"), confusable_skeleton("paypol"));
+    }
+}
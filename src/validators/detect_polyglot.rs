@@ -0,0 +1,287 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Detection of polyglot files : secondary payloads (archive, document, script, executable) hidden after the end of an image or a video.
+
+use crate::{container_end, iso_bmff_boxes, read_u32_le, StructureError};
+use std::fmt;
+use std::ops::Range;
+
+// Kind of payload that can be found after the end of the container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddedSignature {
+    // ZIP local file header, central directory or end of central directory
+    Zip,
+    // PDF header
+    Pdf,
+    // <html or <!doctype html
+    Html,
+    // <script
+    Script,
+    // PHP open tag <?php or <?=
+    Php,
+    // ELF executable header
+    Elf,
+    // Windows PE executable (MZ header pointing to a PE header)
+    PortableExecutable,
+}
+
+impl fmt::Display for EmbeddedSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EmbeddedSignature::Zip => "ZIP archive",
+            EmbeddedSignature::Pdf => "PDF document",
+            EmbeddedSignature::Html => "HTML document",
+            EmbeddedSignature::Script => "script tag",
+            EmbeddedSignature::Php => "PHP code",
+            EmbeddedSignature::Elf => "ELF executable",
+            EmbeddedSignature::PortableExecutable => "PE executable",
+        })
+    }
+}
+
+// A secondary payload found in a file, at the given offset from the start of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddedPayload {
+    pub signature: EmbeddedSignature,
+    pub offset: usize,
+}
+
+// signatures compared byte by byte
+const BINARY_SIGNATURES: [(&[u8], EmbeddedSignature); 5] = [
+    (b"PK\x03\x04", EmbeddedSignature::Zip),
+    (b"PK\x01\x02", EmbeddedSignature::Zip),
+    (b"PK\x05\x06", EmbeddedSignature::Zip),
+    (b"%PDF-", EmbeddedSignature::Pdf),
+    (b"\x7FELF", EmbeddedSignature::Elf),
+];
+
+// signatures compared without case
+const TEXT_SIGNATURES: [(&[u8], EmbeddedSignature); 5] = [
+    (b"<html", EmbeddedSignature::Html),
+    (b"<!doctype html", EmbeddedSignature::Html),
+    (b"<script", EmbeddedSignature::Script),
+    (b"<?php", EmbeddedSignature::Php),
+    (b"<?=", EmbeddedSignature::Php),
+];
+
+// top level boxes of MP4 and MOV files read by the players. The others, like free, skip or wide, are ignored by the players
+// and can hide a payload, thus their content is scanned like the data after the container.
+const ISO_BMFF_BOXES: [&[u8; 4]; 11] = [
+    b"ftyp", b"styp", b"moov", b"mdat", b"moof", b"mfra", b"sidx", b"ssix", b"prft", b"emsg",
+    b"meta",
+];
+
+// Search secondary payloads after the end of the container of the content, according to its MIME type.
+// For MP4 and MOV, the top level boxes ignored by the players are scanned too.
+// If the end of the container can't be computed for this format (e.g. MP3), the whole content is scanned.
+// Return the payloads found, ordered by offset. An empty vector means the file is not a polyglot.
+// Return a StructureError if the container can't be parsed to find its end.
+pub fn detect_polyglot(
+    buffer: &[u8],
+    mime_type: &str,
+) -> Result<Vec<EmbeddedPayload>, StructureError> {
+    let ranges = match mime_type {
+        "video/mp4" | "video/x-m4v" | "video/quicktime" => {
+            let mut ranges = Vec::new();
+            let mut start = 0;
+            for (box_type, end) in iso_bmff_boxes(buffer)? {
+                if !ISO_BMFF_BOXES.contains(&&box_type) {
+                    ranges.push(start..end);
+                }
+                start = end;
+            }
+            ranges.push(start..buffer.len());
+            ranges
+        }
+        _ => {
+            let start = container_end(buffer, mime_type)?.unwrap_or(0);
+            return Ok(scan_signatures(buffer, start));
+        }
+    };
+    Ok(ranges
+        .into_iter()
+        .flat_map(|range| scan_range(buffer, range))
+        .collect())
+}
+
+// Search every known signature in the content, from the given offset.
+pub fn scan_signatures(buffer: &[u8], start: usize) -> Vec<EmbeddedPayload> {
+    scan_range(buffer, start..buffer.len())
+}
+
+// search the signatures starting in the range, a signature can end after it
+fn scan_range(buffer: &[u8], range: Range<usize>) -> Vec<EmbeddedPayload> {
+    let mut payloads = Vec::new();
+    for offset in range {
+        let rest = &buffer[offset..];
+        let signature = BINARY_SIGNATURES
+            .iter()
+            .find(|(magic, _)| rest.starts_with(magic))
+            .or_else(|| {
+                TEXT_SIGNATURES.iter().find(|(magic, _)| {
+                    rest.len() >= magic.len() && rest[..magic.len()].eq_ignore_ascii_case(magic)
+                })
+            })
+            .map(|(_, signature)| *signature)
+            .or_else(|| {
+                is_portable_executable(rest).then_some(EmbeddedSignature::PortableExecutable)
+            });
+        if let Some(signature) = signature {
+            payloads.push(EmbeddedPayload { signature, offset });
+        }
+    }
+    payloads
+}
+
+// a MZ header is only reported if its e_lfanew field points to a PE header, "MZ" alone is too common
+fn is_portable_executable(buffer: &[u8]) -> bool {
+    if !buffer.starts_with(b"MZ") {
+        return false;
+    }
+    match read_u32_le(buffer, 0x3C) {
+        Some(pe_offset) => {
+            buffer.get(pe_offset as usize..pe_offset as usize + 4) == Some(b"PE\0\0")
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{detect_polyglot, scan_signatures, EmbeddedPayload, EmbeddedSignature};
+    use std::fs;
+
+    const IMAGES_PATH: &str = "res/image";
+    const VIDEO_PATH: &str = "res/video";
+
+    #[test]
+    fn clean_media() {
+        for path in fs::read_dir(IMAGES_PATH)
+            .unwrap()
+            .chain(fs::read_dir(VIDEO_PATH).unwrap())
+        {
+            let path = path.unwrap().path();
+            let buffer = fs::read(&path).unwrap();
+            let file_type = infer::get(&buffer).unwrap();
+            assert_eq!(
+                detect_polyglot(&buffer, file_type.mime_type()),
+                Ok(vec![]),
+                "{:?} schould not be a polyglot",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn appended_zip() {
+        let mut buffer = fs::read("res/image/file_example_JPG_100kB.jpg").unwrap();
+        let length = buffer.len();
+        buffer.extend_from_slice(&fs::read("res/other_extension/zip_2MB.zip").unwrap());
+        let payloads = detect_polyglot(&buffer, "image/jpeg").unwrap();
+        assert_eq!(
+            payloads[0],
+            EmbeddedPayload {
+                signature: EmbeddedSignature::Zip,
+                offset: length
+            },
+            "The ZIP appended to the JPG schould be found at the end of the image"
+        );
+    }
+
+    #[test]
+    fn appended_script_after_video() {
+        let mut buffer = fs::read("res/video/file_example_WEBM_480_900KB.webm").unwrap();
+        let length = buffer.len();
+        buffer.extend_from_slice(b"\n<SCRIPT>alert(1)</SCRIPT>");
+        assert_eq!(
+            detect_polyglot(&buffer, "video/webm").unwrap(),
+            vec![EmbeddedPayload {
+                signature: EmbeddedSignature::Script,
+                offset: length + 1
+            }],
+            "Signatures schould be found without case"
+        );
+    }
+
+    #[test]
+    fn hidden_in_free_box() {
+        let mp4 = fs::read("res/video/file_example_MP4_480_1_5MG.mp4").unwrap();
+        let payload = b"<?php system($_GET['cmd']); ?>";
+        let mut free = (8 + payload.len() as u32).to_be_bytes().to_vec();
+        free.extend_from_slice(b"free");
+        free.extend_from_slice(payload);
+        let mut buffer = mp4.clone();
+        buffer.extend_from_slice(&free);
+        assert_eq!(
+            detect_polyglot(&buffer, "video/mp4").unwrap(),
+            vec![EmbeddedPayload {
+                signature: EmbeddedSignature::Php,
+                offset: mp4.len() + 8
+            }],
+            "A payload in a free box after the last known box schould be found"
+        );
+        // the same box between ftyp (32 bytes) and moov
+        let mut buffer = mp4[..32].to_vec();
+        buffer.extend_from_slice(&free);
+        buffer.extend_from_slice(&mp4[32..]);
+        assert_eq!(
+            detect_polyglot(&buffer, "video/mp4").unwrap(),
+            vec![EmbeddedPayload {
+                signature: EmbeddedSignature::Php,
+                offset: 40
+            }],
+            "A payload in a free box between the known boxes schould be found"
+        );
+    }
+
+    #[test]
+    fn unknown_container_end() {
+        let mut buffer = b"ID3\x04\0\0\0\0\0\0".to_vec();
+        buffer.extend_from_slice(b"<script>alert(1)</script>");
+        assert_eq!(
+            detect_polyglot(&buffer, "audio/mpeg"),
+            Ok(vec![EmbeddedPayload {
+                signature: EmbeddedSignature::Script,
+                offset: 10
+            }]),
+            "A format without known end schould be scanned entirely"
+        );
+        let mut buffer = fs::read("res/image/file_example_TIFF_1MB.tif").unwrap();
+        let length = buffer.len();
+        buffer.extend_from_slice(b"<script>alert(1)</script>");
+        assert_eq!(
+            detect_polyglot(&buffer, "image/tiff"),
+            Ok(vec![EmbeddedPayload {
+                signature: EmbeddedSignature::Script,
+                offset: length
+            }])
+        );
+    }
+
+    #[test]
+    fn known_signatures() {
+        let mut pe = b"MZ".to_vec();
+        pe.resize(0x40, 0);
+        pe[0x3C] = 0x40;
+        pe.extend_from_slice(b"PE\0\0");
+        for (payload, signature) in [
+            (b"%PDF-1.4".to_vec(), EmbeddedSignature::Pdf),
+            (b"<!DOCTYPE html>".to_vec(), EmbeddedSignature::Html),
+            (b"<?php echo 1; ?>".to_vec(), EmbeddedSignature::Php),
+            (b"\x7FELF\x02\x01".to_vec(), EmbeddedSignature::Elf),
+            (pe, EmbeddedSignature::PortableExecutable),
+        ] {
+            assert_eq!(
+                scan_signatures(&payload, 0)[0],
+                EmbeddedPayload {
+                    signature,
+                    offset: 0
+                }
+            );
+        }
+        assert!(
+            scan_signatures(b"MZ without PE header", 0).is_empty(),
+            "MZ alone schould not be reported"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Detection of the type of a content with infer, corrected for the formats infer confuses (OOXML and ZIP, PDF and RTF).
+
+use crate::{ooxml_document, zip_entries};
+use infer::{MatcherType, Type};
+
+// MIME types of the ZIP archives and of the formats based on ZIP that infer recognizes from the first local headers only
+pub(crate) const ZIP_MIME_TYPES: [&str; 4] = [
+    "application/zip",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+];
+
+// Type detected from the content of a file.
+// matcher_type : the category of the content, e.g. MatcherType::Image
+// mime_type : the MIME type, e.g. "image/png"
+// extension : the canonical extension of this MIME type, without the dot, e.g. "png"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectedType {
+    pub matcher_type: MatcherType,
+    pub mime_type: &'static str,
+    pub extension: &'static str,
+}
+
+impl From<Type> for DetectedType {
+    fn from(file_type: Type) -> Self {
+        DetectedType {
+            matcher_type: file_type.matcher_type(),
+            mime_type: file_type.mime_type(),
+            extension: file_type.extension(),
+        }
+    }
+}
+
+// Detect the type of the content with its magic number, then correct the result of infer :
+// - a ZIP archive is an OOXML document (docx, xlsx, pptx) only if its central directory contains the parts of one,
+//   whatever the order of the entries. If the central directory can't be read (e.g. only the beginning of the content is given),
+//   the result of infer is kept.
+// - PDF and RTF are documents, not archives.
+// Return None if the type is unknown.
+pub fn detect_type(buffer: &[u8]) -> Option<DetectedType> {
+    let detected = DetectedType::from(infer::get(buffer)?);
+    if ZIP_MIME_TYPES.contains(&detected.mime_type) {
+        if let Ok(entries) = zip_entries(buffer) {
+            return Some(match ooxml_document(&entries) {
+                Some(document) => DetectedType {
+                    matcher_type: MatcherType::Doc,
+                    mime_type: document.mime_type(),
+                    extension: document.extension(),
+                },
+                None => DetectedType {
+                    matcher_type: MatcherType::Archive,
+                    mime_type: "application/zip",
+                    extension: "zip",
+                },
+            });
+        }
+    }
+    if matches!(detected.mime_type, "application/pdf" | "application/rtf") {
+        return Some(DetectedType {
+            matcher_type: MatcherType::Doc,
+            ..detected
+        });
+    }
+    Some(detected)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detect_type;
+    use crate::validators::zip_archive::tests::stored_zip;
+    use infer::MatcherType;
+    use std::fs;
+
+    #[test]
+    fn other_extensions() {
+        for (path, matcher_type, mime_type) in [
+            (
+                "res/other_extension/file-sample_100kB.docx",
+                MatcherType::Doc,
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            ),
+            (
+                "res/other_extension/zip_2MB.zip",
+                MatcherType::Archive,
+                "application/zip",
+            ),
+            (
+                "res/other_extension/file_example_MP3_700KB.mp3",
+                MatcherType::Audio,
+                "audio/mpeg",
+            ),
+        ] {
+            let detected = detect_type(&fs::read(path).unwrap()).unwrap();
+            assert_eq!(detected.matcher_type, matcher_type, "{}", path);
+            assert_eq!(detected.mime_type, mime_type, "{}", path);
+        }
+        assert_eq!(
+            detect_type(&fs::read("res/other_extension/file_example_CSV_5000.csv").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn ooxml_and_zip() {
+        // infer reads the name of the first entry : "word/" makes it a docx
+        let fake_docx = stored_zip(&[("word/payload.exe", b"MZ")]);
+        assert_eq!(
+            infer::get(&fake_docx).unwrap().extension(),
+            "docx",
+            "infer schould be confused by the name of the first entry"
+        );
+        assert_eq!(
+            detect_type(&fake_docx).unwrap().mime_type,
+            "application/zip"
+        );
+        // the parts of a xlsx after other entries
+        let xlsx = stored_zip(&[
+            ("docProps/app.xml", b""),
+            ("xl/workbook.xml", b""),
+            ("[Content_Types].xml", b""),
+        ]);
+        let detected = detect_type(&xlsx).unwrap();
+        assert_eq!(detected.matcher_type, MatcherType::Doc);
+        assert_eq!(detected.extension, "xlsx");
+    }
+
+    #[test]
+    fn pdf_is_document() {
+        let detected = detect_type(b"%PDF-1.7\n%%EOF\n").unwrap();
+        assert_eq!(detected.matcher_type, MatcherType::Doc);
+        assert_eq!(detected.extension, "pdf");
+    }
+}
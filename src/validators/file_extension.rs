@@ -0,0 +1,234 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Extraction of the extension of a filename and table of the extensions accepted for each MIME type.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+// extensions accepted in addition to the canonical extension given by infer, for each MIME type
+const DEFAULT_ALIASES: [(&str, &[&str]); 17] = [
+    ("image/jpeg", &["jpg", "jpeg", "jpe", "jfif"]),
+    ("image/tiff", &["tif", "tiff"]),
+    ("image/heif", &["heif", "heic"]),
+    ("video/mp4", &["mp4", "m4v"]),
+    ("video/x-m4v", &["m4v", "mp4"]),
+    ("video/quicktime", &["mov", "qt"]),
+    ("video/mpeg", &["mpg", "mpeg"]),
+    ("audio/mpeg", &["mp3"]),
+    ("audio/m4a", &["m4a"]),
+    ("audio/ogg", &["ogg", "oga"]),
+    ("audio/x-aiff", &["aif", "aiff"]),
+    ("audio/midi", &["mid", "midi"]),
+    ("audio/x-wav", &["wav", "wave"]),
+    ("application/msword", &["doc", "dot"]),
+    ("application/gzip", &["gz"]),
+    ("application/x-tar", &["tar"]),
+    ("application/x-bzip2", &["bz2"]),
+];
+
+// extensions executed by a web server or a system : a server configured by extension (Apache AddHandler, ...) can
+// execute "shell.php.jpg" even if its final extension is the one of an image
+const DANGEROUS_EXTENSIONS: [&str; 32] = [
+    "php", "php3", "php4", "php5", "php7", "phtml", "pht", "phar", "asp", "aspx", "jsp", "jspx",
+    "cgi", "pl", "py", "rb", "sh", "bash", "exe", "dll", "com", "bat", "cmd", "ps1", "vbs", "js",
+    "mjs", "jar", "html", "htm", "shtml", "xhtml",
+];
+
+// Return the real final extension of a filename, in lower case and without the dot, or None if it has no extension.
+// "photo.JPEG" gives "jpeg", "archive.tar.gz" gives "gz", ".jpg" (hidden file without extension) gives None.
+pub fn file_extension(filename: &str) -> Option<String> {
+    Path::new(filename)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .filter(|extension| !extension.is_empty())
+}
+
+// Return every extension of a filename, in lower case and without the dot, the final one being the last.
+// "archive.tar.GZ" gives ["tar", "gz"], "shell.php.jpg" gives ["php", "jpg"], ".config.jpg" gives ["jpg"].
+pub fn file_extensions(filename: &str) -> Vec<String> {
+    let name = Path::new(filename)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    // the leading dot of a hidden file is not an extension, and neither is its name
+    name.trim_start_matches('.')
+        .split('.')
+        .skip(1)
+        .filter(|extension| !extension.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Return the first extension before the final one that is executed by a web server or a system, like "php" in
+// "shell.php.jpg". Only the final extension is compared to the content, the other ones must not be dangerous.
+pub fn dangerous_extension(filename: &str) -> Option<String> {
+    let mut extensions = file_extensions(filename);
+    extensions.pop();
+    extensions
+        .into_iter()
+        .find(|extension| DANGEROUS_EXTENSIONS.contains(&extension.as_str()))
+}
+
+// Table of the extensions accepted for each MIME type. The canonical extension of the MIME type is always accepted.
+// The default table contains the common aliases (jpeg, jpe, jfif for JPEG, tif for TIFF, m4v for MP4, qt for QuickTime, ...)
+// and can be extended with add().
+#[derive(Debug, Clone)]
+pub struct ExtensionAliases {
+    aliases: HashMap<String, Vec<String>>,
+}
+
+impl Default for ExtensionAliases {
+    fn default() -> Self {
+        let mut aliases = ExtensionAliases {
+            aliases: HashMap::new(),
+        };
+        for (mime_type, extensions) in DEFAULT_ALIASES {
+            for extension in extensions {
+                aliases.add(mime_type, extension);
+            }
+        }
+        aliases
+    }
+}
+
+impl ExtensionAliases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Accept an extension (without the dot, compared without case) for a MIME type.
+    pub fn add(&mut self, mime_type: &str, extension: &str) {
+        let extensions = self.aliases.entry(mime_type.to_lowercase()).or_default();
+        let extension = extension.trim_start_matches('.').to_lowercase();
+        if !extensions.contains(&extension) {
+            extensions.push(extension);
+        }
+    }
+
+    // Return the extensions accepted for a MIME type, in addition to its canonical extension.
+    pub fn extensions(&self, mime_type: &str) -> &[String] {
+        self.aliases
+            .get(mime_type)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    // the comparaison is true if the extension of the filename is the canonical extension or an alias of the MIME type
+    pub fn matches(&self, filename: &str, mime_type: &str, canonical_extension: &str) -> bool {
+        match file_extension(filename) {
+            Some(extension) => {
+                extension == canonical_extension || self.extensions(mime_type).contains(&extension)
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{dangerous_extension, file_extension, file_extensions, ExtensionAliases};
+    use std::fs;
+
+    const MODIFIED_EXTENSION: &str = "res/image_with_modified_extension";
+
+    #[test]
+    fn extract_extension() {
+        assert_eq!(file_extension("photo.JPEG"), Some("jpeg".to_string()));
+        assert_eq!(file_extension("res/image/a.b.png"), Some("png".to_string()));
+        assert_eq!(file_extension("shell.php.jpg"), Some("jpg".to_string()));
+        assert_eq!(file_extension("photo"), None);
+        assert_eq!(
+            file_extension(".jpg"),
+            None,
+            "A hidden file has no extension"
+        );
+        assert_eq!(file_extension("photo."), None);
+    }
+
+    #[test]
+    fn extension_chain() {
+        assert_eq!(file_extensions("backup/archive.tar.GZ"), vec!["tar", "gz"]);
+        assert_eq!(file_extensions("shell.php.jpg"), vec!["php", "jpg"]);
+        assert_eq!(file_extensions(".config.jpg"), vec!["jpg"]);
+        assert_eq!(file_extensions("photo..jpg"), vec!["jpg"]);
+        assert_eq!(file_extensions("photo"), Vec::<String>::new());
+        for filename in [
+            "shell.php.jpg",
+            "shell.PHP5.png",
+            "page.html.gif",
+            "run.exe.tar.gz",
+        ] {
+            assert!(
+                dangerous_extension(filename).is_some(),
+                "{} schould have a dangerous extension",
+                filename
+            );
+        }
+        for filename in [
+            "archive.tar.gz",
+            "holidays.2022.jpg",
+            "photo.jpg",
+            "shell.php",
+            "photo",
+        ] {
+            assert_eq!(dangerous_extension(filename), None, "{}", filename);
+        }
+    }
+
+    #[test]
+    fn default_aliases() {
+        let aliases = ExtensionAliases::new();
+        for filename in [
+            "photo.jpg",
+            "photo.JPG",
+            "photo.jpeg",
+            "photo.JPEG",
+            "photo.jpe",
+            "photo.jfif",
+        ] {
+            assert!(
+                aliases.matches(filename, "image/jpeg", "jpg"),
+                "{} schould match a JPEG",
+                filename
+            );
+        }
+        assert!(aliases.matches("scan.tif", "image/tiff", "tif"));
+        assert!(aliases.matches("scan.TIFF", "image/tiff", "tif"));
+        assert!(aliases.matches("movie.m4v", "video/mp4", "mp4"));
+        assert!(aliases.matches("movie.qt", "video/quicktime", "mov"));
+    }
+
+    #[test]
+    fn invalid_extensions() {
+        let aliases = ExtensionAliases::new();
+        assert!(
+            !aliases.matches("photo.xjpg", "image/jpeg", "jpg"),
+            "Only the real extension schould be compared"
+        );
+        assert!(!aliases.matches("photojpg", "image/jpeg", "jpg"));
+        assert!(!aliases.matches("photo.jpg.exe", "image/jpeg", "jpg"));
+        assert!(!aliases.matches("photo.tif", "image/jpeg", "jpg"));
+        for image_path in fs::read_dir(MODIFIED_EXTENSION).unwrap() {
+            let path = image_path.unwrap().path();
+            let file_type = infer::get_from_path(&path).unwrap().unwrap();
+            assert!(
+                !aliases.matches(
+                    path.to_str().unwrap(),
+                    file_type.mime_type(),
+                    file_type.extension()
+                ),
+                "{:?} schould not match its content",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn custom_aliases() {
+        let mut aliases = ExtensionAliases::new();
+        assert!(!aliases.matches("icon.cur", "image/vnd.microsoft.icon", "ico"));
+        aliases.add("image/vnd.microsoft.icon", ".CUR");
+        assert!(aliases.matches("icon.cur", "image/vnd.microsoft.icon", "ico"));
+        assert_eq!(aliases.extensions("image/vnd.microsoft.icon"), ["cur"]);
+    }
+}
@@ -0,0 +1,953 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : A configurable file validator, that allow every upload endpoint to declare its own policy (categories, MIME types, extension and size).
+
+use crate::{
+    dangerous_extension, detect_polyglot, detect_type, file_extension, image_dimensions,
+    validate_structure, video_metadata, DetectedType, Dimensions, ExtensionAliases,
+    FileValidationError, MediaInfo, MediaKind, ValidatedFile, VideoMetadata, ZIP_MIME_TYPES,
+};
+use infer::MatcherType;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::time::Duration;
+
+// number of bytes read to detect the type of the content before the rest is read, enough for every signature known by infer
+const HEADER_SIZE: usize = 8192;
+
+// maximum size in bytes of a file when no other maximum is given, the content being read in memory to be verified
+pub const DEFAULT_MAX_SIZE: u64 = 100 * 1024 * 1024;
+
+// Minimum and maximum size in bytes of a file.
+#[derive(Debug, Clone, Copy, Default)]
+struct SizeLimits {
+    min: Option<u64>,
+    max: Option<u64>,
+}
+
+// Policy used to accept or refuse a file. Build it with FileValidator::new() and the builder methods, then call validate(path).
+// By default, only images and videos are accepted, the extension is verified, the maximum size is DEFAULT_MAX_SIZE,
+// there is no dimension, duration or codec limit, no deep verification and no polyglot detection.
+#[derive(Debug, Clone)]
+pub struct FileValidator {
+    categories: Vec<MatcherType>,
+    allowed_mime_types: Vec<String>, // if empty, every MIME type of the allowed categories is accepted
+    denied_mime_types: Vec<String>,
+    verify_extension: bool,
+    extension_aliases: ExtensionAliases,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    kind_size_limits: HashMap<MediaKind, SizeLimits>, // replace min_size and max_size for a kind
+    deep_check: bool,
+    reject_polyglots: bool,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    max_pixels: Option<u64>,
+    max_duration: Option<Duration>,
+    allowed_codecs: Vec<String>, // if empty, every codec is accepted
+}
+
+impl Default for FileValidator {
+    fn default() -> Self {
+        FileValidator {
+            categories: vec![MatcherType::Image, MatcherType::Video],
+            allowed_mime_types: Vec::new(),
+            denied_mime_types: Vec::new(),
+            verify_extension: true,
+            extension_aliases: ExtensionAliases::default(),
+            min_size: None,
+            max_size: Some(DEFAULT_MAX_SIZE),
+            kind_size_limits: HashMap::new(),
+            deep_check: false,
+            reject_polyglots: false,
+            max_width: None,
+            max_height: None,
+            max_pixels: None,
+            max_duration: None,
+            allowed_codecs: Vec::new(),
+        }
+    }
+}
+
+impl FileValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replace the accepted categories by the given ones.
+    pub fn categories(mut self, categories: &[MatcherType]) -> Self {
+        self.categories = categories.to_vec();
+        self
+    }
+
+    // Add a category to the accepted ones.
+    pub fn allow_category(mut self, category: MatcherType) -> Self {
+        if !self.categories.contains(&category) {
+            self.categories.push(category);
+        }
+        self
+    }
+
+    // Restrict the accepted files to the given MIME type. Can be called many times to build an allowlist.
+    pub fn allow_mime_type(mut self, mime_type: &str) -> Self {
+        self.allowed_mime_types.push(mime_type.to_lowercase());
+        self
+    }
+
+    // Refuse a MIME type, even if it is in an accepted category or in the allowlist.
+    pub fn deny_mime_type(mut self, mime_type: &str) -> Self {
+        self.denied_mime_types.push(mime_type.to_lowercase());
+        self
+    }
+
+    // if true, the final extension in the filename must match the content of the file, and the extensions before it
+    // must not be dangerous (see dangerous_extension).
+    pub fn verify_extension(mut self, verify_extension: bool) -> Self {
+        self.verify_extension = verify_extension;
+        self
+    }
+
+    // Accept an additional extension for a MIME type, see ExtensionAliases.
+    pub fn extension_alias(mut self, mime_type: &str, extension: &str) -> Self {
+        self.extension_aliases.add(mime_type, extension);
+        self
+    }
+
+    // Maximum size of the file in bytes, DEFAULT_MAX_SIZE if not given.
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    // Minimum size of the file in bytes.
+    pub fn min_size(mut self, min_size: u64) -> Self {
+        self.min_size = Some(min_size);
+        self
+    }
+
+    // Maximum size in bytes of the files of this kind, instead of the maximum size given by max_size.
+    pub fn max_size_for(mut self, kind: MediaKind, max_size: u64) -> Self {
+        self.kind_size_limits.entry(kind).or_default().max = Some(max_size);
+        self
+    }
+
+    // Minimum size in bytes of the files of this kind, instead of the minimum size given by min_size.
+    pub fn min_size_for(mut self, kind: MediaKind, min_size: u64) -> Self {
+        self.kind_size_limits.entry(kind).or_default().min = Some(min_size);
+        self
+    }
+
+    // if true, the structure of the file is parsed (PNG chunks and CRC, JPEG segments, GIF blocks, WebP RIFF size, TIFF IFDs,
+    // ICO directory, MP4 and MOV boxes, AVI RIFF, WebM and Matroska elements, WMV objects, ZIP central directory, PDF trailer)
+    // and truncated, corrupted files or files with data after the end of the container are refused.
+    // The other formats (e.g. BMP, HEIF, AVIF, FLV, MPEG, audio files, fonts) are accepted without verification.
+    pub fn deep_check(mut self, deep_check: bool) -> Self {
+        self.deep_check = deep_check;
+        self
+    }
+
+    // if true, the content after the end of the container is scanned for secondary payloads (ZIP, PDF, HTML, script, PHP, ELF, PE)
+    // and the file is refused if one is found. The whole content is scanned if the end of its container is not known,
+    // and a container that can't be parsed is refused with UnknownContainerEnd. See detect_polyglot.
+    pub fn reject_polyglots(mut self, reject_polyglots: bool) -> Self {
+        self.reject_polyglots = reject_polyglots;
+        self
+    }
+
+    // Maximum width of an image, in pixels. Read from the header, the bitmap is not decoded.
+    pub fn max_width(mut self, max_width: u32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    // Maximum height of an image, in pixels. Read from the header, the bitmap is not decoded.
+    pub fn max_height(mut self, max_height: u32) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    // Maximum number of pixels of an image (width * height).
+    pub fn max_pixels(mut self, max_pixels: u64) -> Self {
+        self.max_pixels = Some(max_pixels);
+        self
+    }
+
+    // Maximum duration of a video, read from the headers of its container.
+    pub fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    // Allow a codec for the tracks of a video, compared without case. Can be called many times to build an allowlist.
+    // The codec is named as in its container, refer to Track (e.g. "avc1" in MP4, "H264" in AVI, "V_VP8" in WebM).
+    pub fn allow_codec(mut self, codec: &str) -> Self {
+        self.allowed_codecs.push(codec.to_lowercase());
+        self
+    }
+
+    // Verify the file at the given path with this policy.
+    // Return the MediaInfo of the file if it is accepted, a FileValidationError with the reason otherwise.
+    pub fn validate<P: AsRef<Path>>(&self, path: P) -> Result<MediaInfo, FileValidationError> {
+        let path = path.as_ref();
+        self.validate_open_file(File::open(path)?, &path.to_string_lossy())
+    }
+
+    // Verify an already opened file with this policy. declared_name is used to verify the extension.
+    pub(crate) fn validate_open_file(
+        &self,
+        file: File,
+        declared_name: &str,
+    ) -> Result<MediaInfo, FileValidationError> {
+        let size = file.metadata()?.len();
+        let content = self.read_content(file, Some(size))?;
+        self.validate_bytes(&content, Some(declared_name))
+    }
+
+    // Open the file at the given path once, verify it with this policy and keep the content that has been verified.
+    // Every later step (UUID, storage, ...) must use the content of the ValidatedFile and never open the path again,
+    // because the file could have been replaced in the meantime.
+    pub fn validate_and_read<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<ValidatedFile, FileValidationError> {
+        let path = path.as_ref();
+        self.read_open_file(File::open(path)?, &path.to_string_lossy())
+    }
+
+    // Read and verify an already opened file with this policy. declared_name is used to verify the extension.
+    pub(crate) fn read_open_file(
+        &self,
+        file: File,
+        declared_name: &str,
+    ) -> Result<ValidatedFile, FileValidationError> {
+        let size = file.metadata()?.len();
+        let content = self.read_content(file, Some(size))?;
+        let info = self.validate_bytes(&content, Some(declared_name))?;
+        Ok(ValidatedFile::new(content, info))
+    }
+
+    // Verify the content read from the reader with this policy. Nothing is written on the disk.
+    // declared_name : the filename given by the user, used to verify the extension. If None, the extension verification fails.
+    pub fn validate_reader<R: Read>(
+        &self,
+        reader: R,
+        declared_name: Option<&str>,
+    ) -> Result<MediaInfo, FileValidationError> {
+        let buffer = self.read_content(reader, None)?;
+        self.validate_bytes(&buffer, declared_name)
+    }
+
+    // Verify an in-memory content with this policy.
+    // declared_name : the filename given by the user, used to verify the extension. If None, the extension verification fails.
+    pub fn validate_bytes(
+        &self,
+        buffer: &[u8],
+        declared_name: Option<&str>,
+    ) -> Result<MediaInfo, FileValidationError> {
+        let file_type = detect_type(buffer);
+        self.check_size(buffer.len() as u64, file_type)?;
+        // the extension is not known. thus it is not accepted.
+        let file_type = file_type.ok_or(FileValidationError::UnknownType)?;
+        if !self.categories.contains(&file_type.matcher_type) {
+            return Err(FileValidationError::DisallowedCategory(
+                file_type.matcher_type,
+            ));
+        }
+        let mime_type = file_type.mime_type;
+        if self.denied_mime_types.iter().any(|m| m == mime_type)
+            || (!self.allowed_mime_types.is_empty()
+                && !self.allowed_mime_types.iter().any(|m| m == mime_type))
+        {
+            return Err(FileValidationError::DisallowedMimeType(
+                mime_type.to_string(),
+            ));
+        }
+        let filename = declared_name.unwrap_or_default();
+        if let Some(extension) = dangerous_extension(filename).filter(|_| self.verify_extension) {
+            return Err(FileValidationError::DangerousExtension(extension));
+        }
+        if self.verify_extension
+            && !self
+                .extension_aliases
+                .matches(filename, mime_type, file_type.extension)
+        {
+            // extension in filename don't match exension in header
+            return Err(FileValidationError::ExtensionMismatch {
+                declared: file_extension(filename).unwrap_or_default(),
+                detected: file_type.extension.to_string(),
+            });
+        }
+        if self.reject_polyglots {
+            let payloads = detect_polyglot(buffer, mime_type)
+                .map_err(FileValidationError::UnknownContainerEnd)?;
+            if !payloads.is_empty() {
+                return Err(FileValidationError::Polyglot(payloads));
+            }
+        }
+        let mut info = MediaInfo::from(file_type);
+        if info.kind == MediaKind::Image {
+            info.dimensions = self.check_dimensions(buffer, mime_type)?;
+        } else if info.kind == MediaKind::Video {
+            info.video = self.check_video(buffer, mime_type)?;
+            info.dimensions = info.video.as_ref().and_then(|video| video.dimensions);
+        }
+        if self.deep_check {
+            validate_structure(buffer, mime_type)?;
+        }
+        Ok(info)
+    }
+
+    // without limits, the dimensions are only informative and a header that can't be read is not an error
+    fn check_dimensions(
+        &self,
+        buffer: &[u8],
+        mime_type: &str,
+    ) -> Result<Option<Dimensions>, FileValidationError> {
+        let has_limits =
+            self.max_width.is_some() || self.max_height.is_some() || self.max_pixels.is_some();
+        if !has_limits {
+            return Ok(image_dimensions(buffer, mime_type).ok().flatten());
+        }
+        let dimensions =
+            image_dimensions(buffer, mime_type)?.ok_or(FileValidationError::UnknownDimensions)?;
+        if self.max_width.is_some_and(|max| dimensions.width > max)
+            || self.max_height.is_some_and(|max| dimensions.height > max)
+            || self.max_pixels.is_some_and(|max| dimensions.pixels() > max)
+        {
+            return Err(FileValidationError::ImageTooLarge(dimensions));
+        }
+        Ok(Some(dimensions))
+    }
+
+    // without limits, the metadata are only informative and a container that can't be read is not an error
+    fn check_video(
+        &self,
+        buffer: &[u8],
+        mime_type: &str,
+    ) -> Result<Option<VideoMetadata>, FileValidationError> {
+        if self.max_duration.is_none() && self.allowed_codecs.is_empty() {
+            return Ok(video_metadata(buffer, mime_type).ok().flatten());
+        }
+        let metadata =
+            video_metadata(buffer, mime_type)?.ok_or(FileValidationError::UnknownVideoMetadata)?;
+        if let Some(max) = self.max_duration {
+            let duration = metadata
+                .duration
+                .ok_or(FileValidationError::UnknownVideoMetadata)?;
+            if duration > max {
+                return Err(FileValidationError::VideoTooLong(duration));
+            }
+        }
+        if !self.allowed_codecs.is_empty() {
+            // without tracks, the codecs can't be verified
+            if metadata.tracks.is_empty() {
+                return Err(FileValidationError::UnknownVideoMetadata);
+            }
+            if let Some(track) = metadata
+                .tracks
+                .iter()
+                .find(|track| !self.allowed_codecs.contains(&track.codec.to_lowercase()))
+            {
+                return Err(FileValidationError::DisallowedCodec(track.codec.clone()));
+            }
+        }
+        Ok(Some(metadata))
+    }
+
+    // Read the content, without reading more than the maximum size of its kind.
+    // The kind is detected from the beginning of the content, then the declared size (from the metadata of a file)
+    // is checked before the rest of the content is read.
+    fn read_content<R: Read>(
+        &self,
+        reader: R,
+        declared_size: Option<u64>,
+    ) -> Result<Vec<u8>, FileValidationError> {
+        let mut reader = BufReader::new(reader);
+        let mut buffer = Vec::new();
+        (&mut reader)
+            .take(HEADER_SIZE as u64)
+            .read_to_end(&mut buffer)?;
+        let file_type = detect_type(&buffer);
+        if let Some(size) = declared_size {
+            self.check_size(size, file_type)?;
+        }
+        match self.read_limit(file_type) {
+            // we read one byte more than the maximum to know if the content is too large
+            Some(max) => {
+                // the beginning can already be bigger than a small maximum
+                buffer.truncate(max.saturating_add(1).try_into().unwrap_or(usize::MAX));
+                reader
+                    .take(max.saturating_add(1) - buffer.len() as u64)
+                    .read_to_end(&mut buffer)?;
+                // the kind detected on the whole content can have a bigger maximum than the one used to read it
+                if buffer.len() as u64 > max {
+                    return Err(FileValidationError::TooLarge {
+                        size: buffer.len() as u64,
+                        max,
+                    });
+                }
+            }
+            None => {
+                reader.read_to_end(&mut buffer)?;
+            }
+        };
+        Ok(buffer)
+    }
+
+    // Maximum number of bytes to read. The beginning of a ZIP archive is not enough to know if it is a document
+    // (docx, xlsx, pptx) or an archive, its central directory is at the end : the strictest maximum of both kinds is used.
+    fn read_limit(&self, file_type: Option<DetectedType>) -> Option<u64> {
+        match file_type {
+            Some(file_type) if ZIP_MIME_TYPES.contains(&file_type.mime_type) => {
+                [MatcherType::Doc, MatcherType::Archive]
+                    .into_iter()
+                    .filter_map(|matcher_type| {
+                        self.size_limits(Some(DetectedType {
+                            matcher_type,
+                            ..file_type
+                        }))
+                        .max
+                    })
+                    .min()
+            }
+            _ => self.size_limits(file_type).max,
+        }
+    }
+
+    // limits of the kind of the content if they are set, the global limits otherwise
+    fn size_limits(&self, file_type: Option<DetectedType>) -> SizeLimits {
+        let kind_limits = file_type
+            .and_then(|file_type| {
+                self.kind_size_limits
+                    .get(&MediaKind::from(file_type.matcher_type))
+            })
+            .copied()
+            .unwrap_or_default();
+        SizeLimits {
+            min: kind_limits.min.or(self.min_size),
+            max: kind_limits.max.or(self.max_size),
+        }
+    }
+
+    fn check_size(
+        &self,
+        size: u64,
+        file_type: Option<DetectedType>,
+    ) -> Result<(), FileValidationError> {
+        let limits = self.size_limits(file_type);
+        match (limits.min, limits.max) {
+            (Some(min), _) if size < min => Err(FileValidationError::TooSmall { size, min }),
+            (_, Some(max)) if size > max => Err(FileValidationError::TooLarge { size, max }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validators::zip_archive::tests::stored_zip;
+    use crate::{
+        detect_type, Dimensions, EmbeddedSignature, FileValidationError, FileValidator, MediaKind,
+        StructureError, DEFAULT_MAX_SIZE,
+    };
+    use infer::MatcherType;
+    use std::fs;
+    use std::io::Read;
+    use std::time::Duration;
+
+    const PNG: &str = "res/image/file_example_PNG_500kB.png";
+    const ICO: &str = "res/image/file_example_favicon.ico";
+    const TIFF: &str = "res/image/file_example_TIFF_1MB.tif";
+    const MP4: &str = "res/video/file_example_MP4_480_1_5MG.mp4";
+    const MP3: &str = "res/other_extension/file_example_MP3_700KB.mp3";
+    const DOCX: &str = "res/other_extension/file-sample_100kB.docx";
+    const ZIP: &str = "res/other_extension/zip_2MB.zip";
+
+    #[test]
+    fn default_policy_accepts_images_and_videos() {
+        let validator = FileValidator::new();
+        assert_eq!(validator.validate(PNG).unwrap().kind, MediaKind::Image);
+        assert_eq!(validator.validate(MP4).unwrap().kind, MediaKind::Video);
+        assert!(matches!(
+            validator.validate(MP3),
+            Err(FileValidationError::DisallowedCategory(MatcherType::Audio))
+        ));
+    }
+
+    #[test]
+    fn mime_type_allowlist() {
+        let validator = FileValidator::new()
+            .categories(&[MatcherType::Image])
+            .allow_mime_type("image/png")
+            .allow_mime_type("image/jpeg")
+            .allow_mime_type("image/webp");
+        assert!(validator.validate(PNG).is_ok(), "PNG is in the allowlist");
+        assert!(
+            matches!(
+                validator.validate(ICO),
+                Err(FileValidationError::DisallowedMimeType(m)) if m == "image/vnd.microsoft.icon"
+            ),
+            "ICO is not in the allowlist"
+        );
+        assert!(
+            matches!(
+                validator.validate(MP4),
+                Err(FileValidationError::DisallowedCategory(MatcherType::Video))
+            ),
+            "Videos are not in the categories anymore"
+        );
+    }
+
+    #[test]
+    fn mime_type_denylist() {
+        let validator = FileValidator::new().deny_mime_type("IMAGE/TIFF");
+        assert!(validator.validate(PNG).is_ok());
+        assert!(matches!(
+            validator.validate(TIFF),
+            Err(FileValidationError::DisallowedMimeType(_))
+        ));
+    }
+
+    #[test]
+    fn additional_category() {
+        let validator = FileValidator::new().allow_category(MatcherType::Audio);
+        assert_eq!(validator.validate(MP3).unwrap().kind, MediaKind::Audio);
+    }
+
+    #[test]
+    fn audio_document_archive_categories() {
+        let validator = FileValidator::new()
+            .categories(&[MatcherType::Audio, MatcherType::Doc, MatcherType::Archive])
+            .deep_check(true)
+            .reject_polyglots(true);
+        for (path, kind, extension) in [
+            (MP3, MediaKind::Audio, "mp3"),
+            (DOCX, MediaKind::Document, "docx"),
+            (ZIP, MediaKind::Archive, "zip"),
+        ] {
+            let info = validator.validate(path).unwrap();
+            assert_eq!(info.kind, kind, "{}", path);
+            assert_eq!(info.extension, extension, "{}", path);
+            assert!(
+                matches!(
+                    FileValidator::new().validate(path),
+                    Err(FileValidationError::DisallowedCategory(_))
+                ),
+                "{} schould be refused by default",
+                path
+            );
+        }
+        let docx = fs::read(DOCX).unwrap();
+        assert!(
+            matches!(
+                validator.validate_bytes(&docx, Some("report.zip")),
+                Err(FileValidationError::ExtensionMismatch { .. })
+            ),
+            "A docx schould not be accepted as a plain ZIP"
+        );
+        assert!(matches!(
+            FileValidator::new()
+                .allow_category(MatcherType::Archive)
+                .validate(DOCX),
+            Err(FileValidationError::DisallowedCategory(MatcherType::Doc))
+        ));
+    }
+
+    #[test]
+    fn extension_verification() {
+        let modified = "res/image_with_modified_extension/file_example_JPG_100kB.png";
+        assert!(matches!(
+            FileValidator::new().validate(modified),
+            Err(FileValidationError::ExtensionMismatch { .. })
+        ));
+        assert!(FileValidator::new()
+            .verify_extension(false)
+            .validate(modified)
+            .is_ok());
+    }
+
+    #[test]
+    fn max_size() {
+        assert!(FileValidator::new()
+            .max_size(1_000_000)
+            .validate(PNG)
+            .is_ok());
+        assert!(matches!(
+            FileValidator::new().max_size(1000).validate(PNG),
+            Err(FileValidationError::TooLarge {
+                size: 512596,
+                max: 1000
+            })
+        ));
+    }
+
+    #[test]
+    fn size_limits_per_kind() {
+        let validator = FileValidator::new()
+            .max_size(1000)
+            .max_size_for(MediaKind::Image, 1_000_000)
+            .min_size_for(MediaKind::Video, 2_000_000);
+        assert!(
+            validator.validate(PNG).is_ok(),
+            "The limit of the kind schould replace the global limit"
+        );
+        assert!(matches!(
+            validator.validate(MP4),
+            Err(FileValidationError::TooSmall { min: 2_000_000, .. })
+        ));
+        assert!(matches!(
+            FileValidator::new().min_size(1_000_000).validate(PNG),
+            Err(FileValidationError::TooSmall {
+                size: 512596,
+                min: 1_000_000
+            })
+        ));
+        assert!(matches!(
+            FileValidator::new()
+                .max_size_for(MediaKind::Video, 1000)
+                .validate(MP4),
+            Err(FileValidationError::TooLarge { max: 1000, .. })
+        ));
+        let buffer = fs::read(MP4).unwrap();
+        assert!(
+            matches!(
+                FileValidator::new()
+                    .max_size_for(MediaKind::Video, 10_000)
+                    .validate_reader(buffer.as_slice(), Some("movie.mp4")),
+                Err(FileValidationError::TooLarge {
+                    size: 10_001,
+                    max: 10_000
+                })
+            ),
+            "The reader schould not be read further than the maximum size of the kind"
+        );
+    }
+
+    #[test]
+    fn in_memory_buffer() {
+        let buffer = fs::read(PNG).unwrap();
+        let validator = FileValidator::new();
+        assert_eq!(
+            validator
+                .validate_bytes(&buffer, Some("upload.png"))
+                .unwrap()
+                .mime_type,
+            "image/png"
+        );
+        assert!(
+            matches!(
+                validator.validate_bytes(&buffer, Some("upload.jpg")),
+                Err(FileValidationError::ExtensionMismatch { .. })
+            ),
+            "The declared name schould be used to verify the extension"
+        );
+        assert!(
+            validator.validate_bytes(&buffer, None).is_err(),
+            "Without declared name, the extension verification schould fail"
+        );
+        assert!(validator
+            .verify_extension(false)
+            .validate_bytes(&buffer, None)
+            .is_ok());
+        assert!(matches!(
+            FileValidator::new().validate_bytes(&[], None),
+            Err(FileValidationError::UnknownType)
+        ));
+    }
+
+    #[test]
+    fn reader() {
+        let file = fs::File::open(MP4).unwrap();
+        assert_eq!(
+            FileValidator::new()
+                .validate_reader(file, Some("movie.mp4"))
+                .unwrap()
+                .kind,
+            MediaKind::Video
+        );
+        let buffer = fs::read(PNG).unwrap();
+        assert!(
+            matches!(
+                FileValidator::new()
+                    .max_size(1000)
+                    .validate_reader(buffer.as_slice(), Some("upload.png")),
+                Err(FileValidationError::TooLarge {
+                    size: 1001,
+                    max: 1000
+                })
+            ),
+            "The reader schould not be read further than the maximum size"
+        );
+    }
+
+    #[test]
+    fn default_max_size() {
+        let path =
+            std::env::temp_dir().join(format!("default_max_size_{}.png", std::process::id()));
+        fs::copy(PNG, &path).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(DEFAULT_MAX_SIZE + 1)
+            .unwrap();
+        let result = FileValidator::new().validate(&path);
+        let unlimited = FileValidator::new().max_size(u64::MAX).validate(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(
+                result,
+                Err(FileValidationError::TooLarge {
+                    max: DEFAULT_MAX_SIZE,
+                    ..
+                })
+            ),
+            "A file schould not be read in memory without a maximum size"
+        );
+        assert!(unlimited.is_ok());
+    }
+
+    #[test]
+    fn zip_read_limit() {
+        // the beginning is a docx for infer, the whole content is an archive : the limit of the archives applies
+        let mut buffer = stored_zip(&[("word/payload.bin", &[0; 20_000])]);
+        assert_eq!(
+            detect_type(&buffer[..8192]).unwrap().matcher_type,
+            MatcherType::Doc
+        );
+        assert_eq!(
+            detect_type(&buffer).unwrap().matcher_type,
+            MatcherType::Archive
+        );
+        let validator = FileValidator::new()
+            .categories(&[MatcherType::Doc, MatcherType::Archive])
+            .max_size_for(MediaKind::Archive, 10_000);
+        assert!(
+            matches!(
+                validator.validate_reader(buffer.as_slice(), Some("upload.zip")),
+                Err(FileValidationError::TooLarge {
+                    size: 10_001,
+                    max: 10_000
+                })
+            ),
+            "A ZIP archive schould not be read further than the maximum size of the archives"
+        );
+        buffer.truncate(9000);
+        assert!(
+            matches!(
+                validator.validate_reader(
+                    buffer.as_slice().chain(std::io::repeat(0).take(1 << 30)),
+                    Some("upload.docx")
+                ),
+                Err(FileValidationError::TooLarge {
+                    size: 10_001,
+                    max: 10_000
+                })
+            ),
+            "The maximum size of the documents schould not be used to read a ZIP archive"
+        );
+    }
+
+    #[test]
+    fn deep_check() {
+        let validator = FileValidator::new().deep_check(true);
+        assert!(validator.validate(PNG).is_ok());
+        let mut buffer = fs::read(PNG).unwrap();
+        buffer.extend_from_slice(b"<?php system($_GET['cmd']); ?>");
+        assert!(
+            FileValidator::new()
+                .validate_bytes(&buffer, Some("upload.png"))
+                .is_ok(),
+            "Without deep verification, only the magic number is checked"
+        );
+        assert!(
+            matches!(
+                validator.validate_bytes(&buffer, Some("upload.png")),
+                Err(FileValidationError::Structure(
+                    StructureError::TrailingData { .. }
+                ))
+            ),
+            "A PNG file with appended data schould not pass the deep verification"
+        );
+    }
+
+    #[test]
+    fn polyglot() {
+        let validator = FileValidator::new().reject_polyglots(true);
+        assert!(validator.validate(MP4).is_ok());
+        let mut buffer = fs::read(MP4).unwrap();
+        buffer.extend_from_slice(b"<html><script>alert(1)</script></html>");
+        match validator.validate_bytes(&buffer, Some("movie.mp4")) {
+            Err(FileValidationError::Polyglot(payloads)) => {
+                assert_eq!(payloads.len(), 2);
+                assert_eq!(payloads[0].signature, EmbeddedSignature::Html);
+                assert_eq!(payloads[1].signature, EmbeddedSignature::Script);
+            }
+            _ => panic!("A video with an appended HTML page schould be reported as a polyglot"),
+        }
+        let mut buffer = fs::read(TIFF).unwrap();
+        buffer.extend_from_slice(b"<script>alert(1)</script>");
+        assert!(
+            matches!(
+                validator.validate_bytes(&buffer, Some("upload.tif")),
+                Err(FileValidationError::Polyglot(_))
+            ),
+            "A TIFF with an appended script schould be reported as a polyglot"
+        );
+        let buffer = fs::read(PNG).unwrap();
+        assert!(
+            matches!(
+                validator.validate_bytes(&buffer[..1000], Some("upload.png")),
+                Err(FileValidationError::UnknownContainerEnd(
+                    StructureError::Truncated
+                ))
+            ),
+            "A container without end schould not be reported as a structure error"
+        );
+    }
+
+    #[test]
+    fn dimension_limits() {
+        let info = FileValidator::new().validate(PNG).unwrap();
+        assert_eq!(
+            info.dimensions,
+            Some(Dimensions {
+                width: 850,
+                height: 566
+            })
+        );
+        assert!(FileValidator::new()
+            .max_width(850)
+            .max_height(566)
+            .max_pixels(850 * 566)
+            .validate(PNG)
+            .is_ok());
+        assert!(matches!(
+            FileValidator::new().max_width(849).validate(PNG),
+            Err(FileValidationError::ImageTooLarge(_))
+        ));
+        assert!(FileValidator::new()
+            .max_pixels(10_000)
+            .validate(ICO)
+            .is_ok());
+        assert!(matches!(
+            FileValidator::new().max_pixels(10_000).validate(TIFF),
+            Err(FileValidationError::ImageTooLarge(Dimensions {
+                width: 650,
+                height: 434
+            }))
+        ));
+        assert!(
+            FileValidator::new()
+                .max_pixels(10_000)
+                .validate(MP4)
+                .is_ok(),
+            "The image limits don't apply to videos"
+        );
+    }
+
+    #[test]
+    fn decompression_bomb() {
+        // a PNG declaring 50000x50000 pixels
+        let mut buffer = fs::read(PNG).unwrap();
+        buffer[16..20].copy_from_slice(&50000u32.to_be_bytes());
+        buffer[20..24].copy_from_slice(&50000u32.to_be_bytes());
+        assert!(matches!(
+            FileValidator::new()
+                .max_pixels(100_000_000)
+                .validate_bytes(&buffer, Some("bomb.png")),
+            Err(FileValidationError::ImageTooLarge(Dimensions {
+                width: 50000,
+                height: 50000
+            }))
+        ));
+    }
+
+    #[test]
+    fn video_limits() {
+        let info = FileValidator::new().validate(MP4).unwrap();
+        assert_eq!(
+            info.dimensions,
+            Some(Dimensions {
+                width: 480,
+                height: 270
+            })
+        );
+        assert!(info.video.unwrap().duration.is_some());
+        assert!(FileValidator::new()
+            .max_duration(Duration::from_secs(31))
+            .allow_codec("AVC1")
+            .allow_codec("mp4a")
+            .validate(MP4)
+            .is_ok());
+        assert!(matches!(
+            FileValidator::new()
+                .max_duration(Duration::from_secs(30))
+                .validate(MP4),
+            Err(FileValidationError::VideoTooLong(_))
+        ));
+        assert!(
+            matches!(
+                FileValidator::new().allow_codec("avc1").validate(MP4),
+                Err(FileValidationError::DisallowedCodec(codec)) if codec == "mp4a"
+            ),
+            "Every track of the video schould use an allowed codec"
+        );
+        assert!(
+            FileValidator::new()
+                .allow_codec("avc1")
+                .validate(PNG)
+                .is_ok(),
+            "The video limits don't apply to images"
+        );
+        // a video whose tracks have been hidden in free boxes
+        let mut buffer = fs::read(MP4).unwrap();
+        for pos in 0..buffer.len() - 4 {
+            if &buffer[pos..pos + 4] == b"trak" {
+                buffer[pos..pos + 4].copy_from_slice(b"free");
+            }
+        }
+        assert!(FileValidator::new()
+            .validate_bytes(&buffer, Some("movie.mp4"))
+            .is_ok());
+        assert!(
+            matches!(
+                FileValidator::new()
+                    .allow_codec("avc1")
+                    .validate_bytes(&buffer, Some("movie.mp4")),
+                Err(FileValidationError::UnknownVideoMetadata)
+            ),
+            "A video without tracks schould not pass a codec allowlist"
+        );
+    }
+
+    #[test]
+    fn extension_alias() {
+        let buffer = fs::read(ICO).unwrap();
+        assert!(FileValidator::new()
+            .validate_bytes(&buffer, Some("pointer.cur"))
+            .is_err());
+        assert!(FileValidator::new()
+            .extension_alias("image/vnd.microsoft.icon", "cur")
+            .validate_bytes(&buffer, Some("pointer.cur"))
+            .is_ok());
+    }
+
+    #[test]
+    fn dangerous_inner_extension() {
+        let buffer = fs::read(ICO).unwrap();
+        assert!(matches!(
+            FileValidator::new().validate_bytes(&buffer, Some("shell.php.ico")),
+            Err(FileValidationError::DangerousExtension(extension)) if extension == "php"
+        ));
+        assert!(FileValidator::new()
+            .validate_bytes(&buffer, Some("favicon.2022.ico"))
+            .is_ok());
+        assert!(FileValidator::new()
+            .verify_extension(false)
+            .validate_bytes(&buffer, Some("shell.php.ico"))
+            .is_ok());
+    }
+}
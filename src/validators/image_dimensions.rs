@@ -0,0 +1,371 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Read the dimensions of an image from its header, without decoding the bitmap, to refuse decompression bombs.
+
+use crate::{
+    color_table_size, read_u16_be, read_u16_le, read_u32_be, read_u32_le, skip_sub_blocks,
+    tiff_byte_order, tiff_ifds, tiff_read_u16, tiff_read_u32, StructureError,
+};
+
+// Width and height of an image, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Dimensions {
+    // total number of pixels of the image
+    pub fn pixels(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+}
+
+// Read the dimensions declared in the header of the image, according to its MIME type.
+// Supported formats are GIF, JPEG, PNG, TIFF, WebP and ICO. For an ICO, a TIFF or a GIF, the biggest width and height
+// of its images are returned.
+// Return Ok(None) if the format is not supported.
+pub fn image_dimensions(
+    buffer: &[u8],
+    mime_type: &str,
+) -> Result<Option<Dimensions>, StructureError> {
+    let dimensions = match mime_type {
+        "image/png" => png_dimensions(buffer)?,
+        "image/jpeg" => jpeg_dimensions(buffer)?,
+        "image/gif" => gif_dimensions(buffer)?,
+        "image/tiff" => tiff_dimensions(buffer)?,
+        "image/webp" => webp_dimensions(buffer)?,
+        "image/vnd.microsoft.icon" => ico_dimensions(buffer)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(dimensions))
+}
+
+// PNG : width and height are the first fields of the IHDR chunk, which must be the first chunk
+fn png_dimensions(buffer: &[u8]) -> Result<Dimensions, StructureError> {
+    if buffer.get(12..16) != Some(b"IHDR") {
+        return Err(StructureError::MissingChunk("IHDR"));
+    }
+    Ok(Dimensions {
+        width: read_u32_be(buffer, 16).ok_or(StructureError::Truncated)?,
+        height: read_u32_be(buffer, 20).ok_or(StructureError::Truncated)?,
+    })
+}
+
+// JPEG : the dimensions are in the first SOFn segment
+fn jpeg_dimensions(buffer: &[u8]) -> Result<Dimensions, StructureError> {
+    let mut pos = 2;
+    loop {
+        let marker = *buffer.get(pos + 1).ok_or(StructureError::Truncated)?;
+        if buffer[pos] != 0xFF {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+        match marker {
+            0xFF => pos += 1,
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            // SOFn, except DHT (C4), JPG (C8) and DAC (CC)
+            0xC0..=0xCF if marker != 0xC4 && marker != 0xC8 && marker != 0xCC => {
+                return Ok(Dimensions {
+                    height: read_u16_be(buffer, pos + 5).ok_or(StructureError::Truncated)? as u32,
+                    width: read_u16_be(buffer, pos + 7).ok_or(StructureError::Truncated)? as u32,
+                });
+            }
+            // SOS or EOI before any SOFn
+            0xDA | 0xD9 => return Err(StructureError::MissingChunk("SOF")),
+            _ => {
+                let length = read_u16_be(buffer, pos + 2).ok_or(StructureError::Truncated)?;
+                if length < 2 {
+                    return Err(StructureError::InvalidSegment { offset: pos });
+                }
+                pos += 2 + length as usize;
+            }
+        }
+    }
+}
+
+// GIF : the logical screen descriptor follows the signature, but each image descriptor places a frame in the screen
+// that can go beyond it : the biggest extent of the screen and of the frames is returned.
+// The frames after the end of a truncated buffer are ignored.
+fn gif_dimensions(buffer: &[u8]) -> Result<Dimensions, StructureError> {
+    let mut dimensions = Dimensions {
+        width: read_u16_le(buffer, 6).ok_or(StructureError::Truncated)? as u32,
+        height: read_u16_le(buffer, 8).ok_or(StructureError::Truncated)? as u32,
+    };
+    let flags = *buffer.get(10).ok_or(StructureError::Truncated)?;
+    let mut pos = 13 + color_table_size(flags);
+    while let Some(&block) = buffer.get(pos) {
+        let next = match block {
+            // trailer
+            0x3B => break,
+            // extension : introducer, label and sub-blocks
+            0x21 => skip_sub_blocks(buffer, pos + 2),
+            // image descriptor : left, top, width, height and flags, then the optional local color table,
+            // the LZW minimum code size and the sub-blocks
+            0x2C => {
+                let read = |offset| read_u16_le(buffer, pos + offset).map(u32::from);
+                let (Some(left), Some(top), Some(width), Some(height), Some(&flags)) =
+                    (read(1), read(3), read(5), read(7), buffer.get(pos + 9))
+                else {
+                    break;
+                };
+                dimensions.width = dimensions.width.max(left + width);
+                dimensions.height = dimensions.height.max(top + height);
+                skip_sub_blocks(buffer, pos + 10 + color_table_size(flags) + 1)
+            }
+            _ => return Err(StructureError::InvalidSegment { offset: pos }),
+        };
+        let Ok(next) = next else {
+            break;
+        };
+        pos = next;
+    }
+    Ok(dimensions)
+}
+
+// TIFF : ImageWidth (256) and ImageLength (257) tags of each IFD, in the byte order given by the header.
+// A TIFF file can have many pages : the biggest width and height of its pages are returned.
+fn tiff_dimensions(buffer: &[u8]) -> Result<Dimensions, StructureError> {
+    let little_endian = tiff_byte_order(buffer)?;
+    let read_u16 = |pos| tiff_read_u16(buffer, little_endian, pos).ok_or(StructureError::Truncated);
+    let read_u32 = |pos| tiff_read_u32(buffer, little_endian, pos).ok_or(StructureError::Truncated);
+    let mut dimensions = Dimensions {
+        width: 0,
+        height: 0,
+    };
+    for ifd in tiff_ifds(buffer)? {
+        let count = read_u16(ifd)? as usize;
+        let (mut width, mut height) = (None, None);
+        for entry in (ifd + 2..ifd + 2 + 12 * count).step_by(12) {
+            let tag = read_u16(entry)?;
+            // the value is a SHORT (3) or a LONG (4), stored in the entry
+            let value = match read_u16(entry + 2)? {
+                3 => read_u16(entry + 8)? as u32,
+                4 => read_u32(entry + 8)?,
+                _ => continue,
+            };
+            match tag {
+                256 => width = Some(value),
+                257 => height = Some(value),
+                _ => {}
+            }
+        }
+        let (Some(width), Some(height)) = (width, height) else {
+            return Err(StructureError::MissingChunk("ImageWidth/ImageLength"));
+        };
+        dimensions.width = dimensions.width.max(width);
+        dimensions.height = dimensions.height.max(height);
+    }
+    Ok(dimensions)
+}
+
+// WebP : the dimensions are in the first chunk, with a different layout for lossy (VP8), lossless (VP8L) and extended (VP8X) files
+fn webp_dimensions(buffer: &[u8]) -> Result<Dimensions, StructureError> {
+    let data = 20;
+    match buffer.get(12..16) {
+        Some(b"VP8 ") => {
+            // frame tag (3 bytes) and start code 9D 01 2A, then 14 bits width and height
+            if buffer.get(data + 3..data + 6) != Some(&[0x9D, 0x01, 0x2A]) {
+                return Err(StructureError::InvalidSegment { offset: data });
+            }
+            Ok(Dimensions {
+                width: (read_u16_le(buffer, data + 6).ok_or(StructureError::Truncated)? & 0x3FFF)
+                    as u32,
+                height: (read_u16_le(buffer, data + 8).ok_or(StructureError::Truncated)? & 0x3FFF)
+                    as u32,
+            })
+        }
+        Some(b"VP8L") => {
+            // signature 0x2F, then 14 bits width - 1 and 14 bits height - 1
+            if buffer.get(data) != Some(&0x2F) {
+                return Err(StructureError::InvalidSegment { offset: data });
+            }
+            let bits = read_u32_le(buffer, data + 1).ok_or(StructureError::Truncated)?;
+            Ok(Dimensions {
+                width: (bits & 0x3FFF) + 1,
+                height: ((bits >> 14) & 0x3FFF) + 1,
+            })
+        }
+        Some(b"VP8X") => {
+            // flags (4 bytes), then 24 bits canvas width - 1 and 24 bits canvas height - 1
+            let read_u24 = |pos: usize| {
+                buffer
+                    .get(pos..pos + 3)
+                    .map(|b| b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16)
+                    .ok_or(StructureError::Truncated)
+            };
+            Ok(Dimensions {
+                width: read_u24(data + 4)? + 1,
+                height: read_u24(data + 7)? + 1,
+            })
+        }
+        _ => Err(StructureError::MissingChunk("VP8")),
+    }
+}
+
+// ICO : each entry of the directory declares the size of its image on one byte, which can be a lie.
+// The header of each image is read instead : the IHDR chunk of a PNG image, or the BITMAPINFOHEADER of a bitmap,
+// whose height counts the XOR and the AND masks.
+fn ico_dimensions(buffer: &[u8]) -> Result<Dimensions, StructureError> {
+    let count = read_u16_le(buffer, 4).ok_or(StructureError::Truncated)? as usize;
+    let mut dimensions = Dimensions {
+        width: 0,
+        height: 0,
+    };
+    for entry in (6..6 + 16 * count).step_by(16) {
+        let offset = read_u32_le(buffer, entry + 12).ok_or(StructureError::Truncated)? as usize;
+        let image = buffer.get(offset..).ok_or(StructureError::Truncated)?;
+        let image = if image.starts_with(&[0x89, b'P', b'N', b'G']) {
+            png_dimensions(image)?
+        } else {
+            bitmap_dimensions(image, offset)?
+        };
+        dimensions.width = dimensions.width.max(image.width);
+        dimensions.height = dimensions.height.max(image.height);
+    }
+    Ok(dimensions)
+}
+
+// bitmap of an ICO : a BITMAPCOREHEADER (12 bytes, unsigned 16 bits dimensions) or a BITMAPINFOHEADER and its
+// successors (at least 40 bytes, signed 32 bits dimensions, a negative height meaning a top-down bitmap)
+fn bitmap_dimensions(image: &[u8], offset: usize) -> Result<Dimensions, StructureError> {
+    let (width, height) = match read_u32_le(image, 0).ok_or(StructureError::Truncated)? {
+        12 => (
+            read_u16_le(image, 4).ok_or(StructureError::Truncated)? as u32,
+            read_u16_le(image, 6).ok_or(StructureError::Truncated)? as u32,
+        ),
+        size if size >= 40 => (
+            (read_u32_le(image, 4).ok_or(StructureError::Truncated)? as i32).unsigned_abs(),
+            (read_u32_le(image, 8).ok_or(StructureError::Truncated)? as i32).unsigned_abs(),
+        ),
+        _ => return Err(StructureError::InvalidSegment { offset }),
+    };
+    Ok(Dimensions {
+        width,
+        height: height / 2,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{image_dimensions, Dimensions, StructureError};
+    use std::fs;
+
+    fn dimensions_of(path: &str) -> Option<Dimensions> {
+        let buffer = fs::read(path).unwrap();
+        image_dimensions(&buffer, infer::get(&buffer).unwrap().mime_type()).unwrap()
+    }
+
+    #[test]
+    fn images_dimensions() {
+        for (path, width, height) in [
+            ("res/image/file_example_GIF_500kB.gif", 1900, 1267),
+            ("res/image/file_example_JPG_100kB.jpg", 1050, 700),
+            ("res/image/file_example_PNG_500kB.png", 850, 566),
+            ("res/image/file_example_TIFF_1MB.tif", 650, 434),
+            ("res/image/file_example_WEBP_50kB.webp", 1050, 700),
+            ("res/image/file_example_favicon.ico", 15, 17),
+        ] {
+            assert_eq!(
+                dimensions_of(path),
+                Some(Dimensions { width, height }),
+                "{} schould have the dimensions of its header",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn png_bomb_header() {
+        let mut buffer = fs::read("res/image/file_example_PNG_500kB.png").unwrap();
+        buffer[16..20].copy_from_slice(&50000u32.to_be_bytes());
+        buffer[20..24].copy_from_slice(&50000u32.to_be_bytes());
+        let dimensions = image_dimensions(&buffer, "image/png").unwrap().unwrap();
+        assert_eq!(dimensions.pixels(), 2_500_000_000);
+    }
+
+    #[test]
+    fn frames_and_icons_bigger_than_declared() {
+        // a GIF whose logical screen is 1x1 and whose frame is 5000x4000 at (10, 20)
+        let mut gif = b"GIF89a\x01\x00\x01\x00\x00\x00\x00".to_vec();
+        gif.extend_from_slice(&[0x2C, 10, 0, 20, 0]);
+        gif.extend_from_slice(&5000u16.to_le_bytes());
+        gif.extend_from_slice(&4000u16.to_le_bytes());
+        gif.extend_from_slice(&[0, 2, 1, 0, 0, 0x3B]);
+        assert_eq!(
+            image_dimensions(&gif, "image/gif"),
+            Ok(Some(Dimensions {
+                width: 5010,
+                height: 4020
+            }))
+        );
+        // the frame schould be read even if the GIF is truncated after its descriptor
+        assert_eq!(
+            image_dimensions(&gif[..23], "image/gif")
+                .unwrap()
+                .unwrap()
+                .width,
+            5010
+        );
+        // an ICO whose directory declares 16x16 pixels for a bitmap of 5000x5000 pixels
+        let mut ico = fs::read("res/image/file_example_favicon.ico").unwrap();
+        ico[0x26 + 4..0x26 + 8].copy_from_slice(&5000i32.to_le_bytes());
+        ico[0x26 + 8..0x26 + 12].copy_from_slice(&(-10000i32).to_le_bytes());
+        assert_eq!(
+            image_dimensions(&ico, "image/vnd.microsoft.icon"),
+            Ok(Some(Dimensions {
+                width: 5000,
+                height: 5000
+            }))
+        );
+        ico[0x26] = 20;
+        assert_eq!(
+            image_dimensions(&ico, "image/vnd.microsoft.icon"),
+            Err(StructureError::InvalidSegment { offset: 0x26 })
+        );
+    }
+
+    #[test]
+    fn tiff_pages() {
+        // a TIFF whose first page is 1x1 and whose second page is 60000x60000
+        let page = |next: u32, size: u32| {
+            let mut ifd = 2u16.to_le_bytes().to_vec();
+            for tag in [256u16, 257] {
+                ifd.extend_from_slice(&tag.to_le_bytes());
+                ifd.extend_from_slice(&4u16.to_le_bytes());
+                ifd.extend_from_slice(&1u32.to_le_bytes());
+                ifd.extend_from_slice(&size.to_le_bytes());
+            }
+            ifd.extend_from_slice(&next.to_le_bytes());
+            ifd
+        };
+        let mut tiff = b"II*\0\x08\0\0\0".to_vec();
+        tiff.extend_from_slice(&page(38, 1));
+        tiff.extend_from_slice(&page(0, 60000));
+        assert_eq!(
+            image_dimensions(&tiff, "image/tiff"),
+            Ok(Some(Dimensions {
+                width: 60000,
+                height: 60000
+            }))
+        );
+        // the second page points back to the first one
+        tiff[34..38].copy_from_slice(&8u32.to_le_bytes());
+        assert_eq!(
+            image_dimensions(&tiff, "image/tiff"),
+            Err(StructureError::InvalidSegment { offset: 8 })
+        );
+    }
+
+    #[test]
+    fn invalid_headers() {
+        assert_eq!(
+            image_dimensions(b"\xFF\xD8\xFF\xD9", "image/jpeg"),
+            Err(StructureError::MissingChunk("SOF"))
+        );
+        assert_eq!(
+            image_dimensions(b"GIF89a", "image/gif"),
+            Err(StructureError::Truncated)
+        );
+        assert_eq!(image_dimensions(b"BM", "image/bmp"), Ok(None));
+    }
+}
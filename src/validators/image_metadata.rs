@@ -0,0 +1,860 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Inspection and removal of the metadata (EXIF, XMP, IPTC, comments) of an image, which can reveal the location, the device or the owner.
+
+use crate::{
+    read_u16_be, read_u16_le, read_u32_be, read_u32_le, skip_entropy_coded_data, StructureError,
+};
+use std::ops::Range;
+
+// EXIF and TIFF tags pointing to a sub-IFD, or containing a metadata block
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_INTEROPERABILITY_IFD: u16 = 0xA005;
+const TAG_XMP: u16 = 0x02BC;
+const TAG_IPTC: u16 = 0x83BB;
+const TAG_PHOTOSHOP: u16 = 0x8649;
+
+// tags of a TIFF image that only contain metadata and are not needed to decode the image :
+// DocumentName, ImageDescription, Make, Model, Software, DateTime, Artist, HostComputer, Copyright, XMP, IPTC, Photoshop,
+// EXIF and GPS IFDs, XPTitle, XPComment, XPAuthor, XPKeywords, XPSubject and CameraSerialNumber
+const TIFF_METADATA_TAGS: [u16; 20] = [
+    0x010D,
+    0x010E,
+    0x010F,
+    0x0110,
+    0x0131,
+    0x0132,
+    0x013B,
+    0x013C,
+    0x8298,
+    TAG_XMP,
+    TAG_IPTC,
+    TAG_PHOTOSHOP,
+    TAG_EXIF_IFD,
+    TAG_GPS_IFD,
+    0x9C9B,
+    0x9C9C,
+    0x9C9D,
+    0x9C9E,
+    0x9C9F,
+    0xC62F,
+];
+
+// a malicious EXIF block could chain a huge number of IFDs
+const MAX_IFDS: usize = 32;
+
+// identifiers of the JPEG APP1 segments
+const EXIF_IDENTIFIER: &[u8] = b"Exif\0\0";
+const XMP_IDENTIFIERS: [&[u8]; 2] = [
+    b"http://ns.adobe.com/xap/1.0/\0",
+    b"http://ns.adobe.com/xmp/extension/\0",
+];
+
+// flags of the VP8X chunk of a WebP announcing EXIF and XMP chunks
+const VP8X_EXIF_FLAG: u8 = 0x08;
+const VP8X_XMP_FLAG: u8 = 0x04;
+
+// Kind of metadata block found in an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataKind {
+    // EXIF (JPEG APP1, PNG eXIf, WebP EXIF), or the metadata tags of a TIFF
+    Exif,
+    // XMP packet
+    Xmp,
+    // IPTC-IIM or Photoshop resources (JPEG APP13)
+    Iptc,
+    // comment or textual metadata (JPEG COM, PNG tEXt, zTXt and iTXt)
+    Text,
+    // time of the last modification (PNG tIME)
+    Timestamp,
+}
+
+// Privacy-sensitive information found in the metadata of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SensitiveField {
+    // GPS coordinates, or the city or country where the image has been taken
+    Location,
+    // serial number of the camera body or of the lens
+    SerialNumber,
+    // unique identifier of the image or of the document
+    UniqueId,
+    // make, model or name of the device, or of the lens
+    Device,
+    // name of the author or of the owner of the camera
+    Owner,
+    // date when the image has been taken or created
+    DateTime,
+}
+
+// Metadata found in an image.
+// blocks : the metadata blocks, in the order of the file
+// sensitive : the privacy-sensitive fields found in these blocks, sorted and without duplicates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageMetadata {
+    pub blocks: Vec<MetadataKind>,
+    pub sensitive: Vec<SensitiveField>,
+}
+
+impl ImageMetadata {
+    // true if the image contains no metadata block
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+// a metadata block of a JPEG, PNG or WebP : the bytes to remove, and the bytes to inspect
+struct MetadataBlock {
+    kind: MetadataKind,
+    segment: Range<usize>,
+    payload: Range<usize>,
+}
+
+// List the metadata of the image, according to its MIME type.
+// Supported formats are JPEG, PNG, WebP and TIFF. Return Ok(None) if the format is not supported.
+pub fn image_metadata(
+    buffer: &[u8],
+    mime_type: &str,
+) -> Result<Option<ImageMetadata>, StructureError> {
+    if mime_type == "image/tiff" {
+        return tiff_metadata(buffer).map(Some);
+    }
+    let blocks = match metadata_blocks(buffer, mime_type)? {
+        Some(blocks) => blocks,
+        None => return Ok(None),
+    };
+    let mut sensitive = Vec::new();
+    for block in &blocks {
+        let payload = &buffer[block.payload.clone()];
+        match block.kind {
+            // a malformed EXIF block is removed anyway, the fields read before the error are kept
+            MetadataKind::Exif => exif_fields(payload, &mut sensitive).unwrap_or_default(),
+            MetadataKind::Xmp => xmp_fields(payload, &mut sensitive),
+            MetadataKind::Iptc => iptc_fields(payload, &mut sensitive),
+            MetadataKind::Text => text_fields(payload, &mut sensitive),
+            MetadataKind::Timestamp => sensitive.push(SensitiveField::DateTime),
+        }
+    }
+    sensitive.sort();
+    sensitive.dedup();
+    Ok(Some(ImageMetadata {
+        blocks: blocks.iter().map(|block| block.kind).collect(),
+        sensitive,
+    }))
+}
+
+// Return a copy of the image without its metadata, according to its MIME type. The encoded pixel data is not modified.
+// The color profiles (JPEG APP2, PNG iCCP, WebP ICCP) are kept. The EXIF orientation is removed with the EXIF block.
+// Supported formats are JPEG, PNG, WebP and TIFF. Return Ok(None) if the format is not supported.
+pub fn strip_metadata(buffer: &[u8], mime_type: &str) -> Result<Option<Vec<u8>>, StructureError> {
+    if mime_type == "image/tiff" {
+        return strip_tiff(buffer).map(Some);
+    }
+    let blocks = match metadata_blocks(buffer, mime_type)? {
+        Some(blocks) => blocks,
+        None => return Ok(None),
+    };
+    let mut stripped = Vec::with_capacity(buffer.len());
+    let mut pos = 0;
+    for block in &blocks {
+        stripped.extend_from_slice(&buffer[pos..block.segment.start]);
+        pos = block.segment.end;
+    }
+    stripped.extend_from_slice(&buffer[pos..]);
+    if mime_type == "image/webp" {
+        // the RIFF size, and the VP8X flags of the removed chunks, must be updated
+        let size = (stripped.len() - 8) as u32;
+        stripped[4..8].copy_from_slice(&size.to_le_bytes());
+        if stripped.get(12..16) == Some(b"VP8X") && stripped.len() > 20 {
+            stripped[20] &= !(VP8X_EXIF_FLAG | VP8X_XMP_FLAG);
+        }
+    }
+    Ok(Some(stripped))
+}
+
+// Find the metadata blocks of a JPEG, PNG or WebP. Return Ok(None) for the other formats.
+fn metadata_blocks(
+    buffer: &[u8],
+    mime_type: &str,
+) -> Result<Option<Vec<MetadataBlock>>, StructureError> {
+    let blocks = match mime_type {
+        "image/jpeg" => jpeg_blocks(buffer)?,
+        "image/png" => png_blocks(buffer)?,
+        "image/webp" => webp_blocks(buffer)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(blocks))
+}
+
+// JPEG : APP1 segments (EXIF or XMP), APP13 segments (IPTC) and COM segments, until the EOI marker
+fn jpeg_blocks(buffer: &[u8]) -> Result<Vec<MetadataBlock>, StructureError> {
+    if !buffer.starts_with(&[0xFF, 0xD8]) {
+        return Err(StructureError::InvalidSignature);
+    }
+    let mut blocks = Vec::new();
+    let mut pos = 2;
+    loop {
+        let marker = *buffer.get(pos + 1).ok_or(StructureError::Truncated)?;
+        if buffer[pos] != 0xFF {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+        match marker {
+            0xFF => pos += 1,
+            0xD9 => return Ok(blocks),
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            0x00 | 0xD8 => return Err(StructureError::InvalidSegment { offset: pos }),
+            _ => {
+                let length = read_u16_be(buffer, pos + 2).ok_or(StructureError::Truncated)?;
+                if length < 2 {
+                    return Err(StructureError::InvalidSegment { offset: pos });
+                }
+                let end = pos + 2 + length as usize;
+                let payload = buffer.get(pos + 4..end).ok_or(StructureError::Truncated)?;
+                let kind = match marker {
+                    0xE1 if payload.starts_with(EXIF_IDENTIFIER) => Some(MetadataKind::Exif),
+                    0xE1 if XMP_IDENTIFIERS.iter().any(|id| payload.starts_with(id)) => {
+                        Some(MetadataKind::Xmp)
+                    }
+                    0xED => Some(MetadataKind::Iptc),
+                    0xFE => Some(MetadataKind::Text),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    let identifier = match kind {
+                        MetadataKind::Exif => EXIF_IDENTIFIER.len(),
+                        _ => 0,
+                    };
+                    blocks.push(MetadataBlock {
+                        kind,
+                        segment: pos..end,
+                        payload: pos + 4 + identifier..end,
+                    });
+                }
+                pos = end;
+                if marker == 0xDA {
+                    pos = skip_entropy_coded_data(buffer, pos)?;
+                }
+            }
+        }
+    }
+}
+
+// PNG : eXIf, tEXt, zTXt, iTXt and tIME chunks. The textual chunks can contain XMP, or EXIF and IPTC encoded by ImageMagick.
+fn png_blocks(buffer: &[u8]) -> Result<Vec<MetadataBlock>, StructureError> {
+    if !buffer.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        return Err(StructureError::InvalidSignature);
+    }
+    let mut blocks = Vec::new();
+    let mut pos = 8;
+    while pos < buffer.len() {
+        let length = read_u32_be(buffer, pos).ok_or(StructureError::Truncated)? as usize;
+        let end = pos + 12 + length;
+        let chunk_type = buffer
+            .get(pos + 4..pos + 8)
+            .ok_or(StructureError::Truncated)?;
+        if end > buffer.len() {
+            return Err(StructureError::Truncated);
+        }
+        let payload = pos + 8..end - 4;
+        let kind = match chunk_type {
+            b"eXIf" => Some(MetadataKind::Exif),
+            b"tEXt" | b"zTXt" | b"iTXt" => {
+                let keyword = buffer[payload.clone()].split(|b| *b == 0).next();
+                Some(match keyword.unwrap_or_default() {
+                    b"XML:com.adobe.xmp" => MetadataKind::Xmp,
+                    b"Raw profile type exif" | b"Raw profile type APP1" => MetadataKind::Exif,
+                    b"Raw profile type iptc" => MetadataKind::Iptc,
+                    _ => MetadataKind::Text,
+                })
+            }
+            b"tIME" => Some(MetadataKind::Timestamp),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let payload = match (chunk_type, kind) {
+                (b"eXIf", _) | (b"tEXt", _) => payload,
+                // only the text of an uncompressed iTXt XMP can be inspected
+                (b"iTXt", MetadataKind::Xmp) => itxt_text(&buffer[payload.clone()])
+                    .map(|text| payload.end - text.len()..payload.end)
+                    .unwrap_or(payload.end..payload.end),
+                (_, MetadataKind::Text) => payload,
+                _ => payload.end..payload.end,
+            };
+            blocks.push(MetadataBlock {
+                kind,
+                segment: pos..end,
+                payload,
+            });
+        }
+        if chunk_type == b"IEND" {
+            break;
+        }
+        pos = end;
+    }
+    Ok(blocks)
+}
+
+// iTXt : keyword, compression flag, compression method, language tag, translated keyword and text. None if the text is compressed.
+fn itxt_text(payload: &[u8]) -> Option<&[u8]> {
+    let keyword_end = payload.iter().position(|b| *b == 0)?;
+    if payload.get(keyword_end + 1) != Some(&0) {
+        return None;
+    }
+    let mut fields = payload.get(keyword_end + 3..)?.splitn(3, |b| *b == 0);
+    fields.next()?;
+    fields.next()?;
+    fields.next()
+}
+
+// WebP : EXIF and "XMP " chunks, announced by the flags of the VP8X chunk
+fn webp_blocks(buffer: &[u8]) -> Result<Vec<MetadataBlock>, StructureError> {
+    if buffer.len() < 12 || &buffer[0..4] != b"RIFF" || &buffer[8..12] != b"WEBP" {
+        return Err(StructureError::InvalidSignature);
+    }
+    let mut blocks = Vec::new();
+    let mut pos = 12;
+    while pos < buffer.len() {
+        let size = read_u32_le(buffer, pos + 4).ok_or(StructureError::Truncated)? as usize;
+        let data_end = pos + 8 + size;
+        // the chunks are padded to an even size
+        let end = (data_end + (size & 1)).min(buffer.len());
+        if data_end > buffer.len() {
+            return Err(StructureError::Truncated);
+        }
+        let kind = match &buffer[pos..pos + 4] {
+            b"EXIF" => Some(MetadataKind::Exif),
+            b"XMP " => Some(MetadataKind::Xmp),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            // some encoders keep the identifier of the JPEG APP1 segment
+            let identifier = match buffer[pos + 8..data_end].starts_with(EXIF_IDENTIFIER) {
+                true if kind == MetadataKind::Exif => EXIF_IDENTIFIER.len(),
+                _ => 0,
+            };
+            blocks.push(MetadataBlock {
+                kind,
+                segment: pos..end,
+                payload: pos + 8 + identifier..data_end,
+            });
+        }
+        pos = end;
+    }
+    Ok(blocks)
+}
+
+// TIFF : the metadata are tags of the IFDs of the image itself
+fn tiff_metadata(buffer: &[u8]) -> Result<ImageMetadata, StructureError> {
+    let tiff = Tiff::new(buffer)?;
+    let mut blocks = Vec::new();
+    for (pointer, ifd) in tiff.ifds()? {
+        if pointer.is_some() {
+            continue;
+        }
+        for entry in tiff.entries(ifd)? {
+            let kind = match entry.tag {
+                TAG_XMP => MetadataKind::Xmp,
+                TAG_IPTC | TAG_PHOTOSHOP => MetadataKind::Iptc,
+                tag if TIFF_METADATA_TAGS.contains(&tag) => MetadataKind::Exif,
+                _ => continue,
+            };
+            if !blocks.contains(&kind) {
+                blocks.push(kind);
+            }
+        }
+    }
+    let mut sensitive = Vec::new();
+    exif_fields(buffer, &mut sensitive)?;
+    sensitive.sort();
+    sensitive.dedup();
+    Ok(ImageMetadata { blocks, sensitive })
+}
+
+// TIFF : the metadata tags are removed from the IFDs, and their values are overwritten with zeros.
+// The sub-IFDs (EXIF, GPS) are erased. Nothing is moved, so the offsets of the image data stay valid.
+fn strip_tiff(buffer: &[u8]) -> Result<Vec<u8>, StructureError> {
+    let tiff = Tiff::new(buffer)?;
+    let mut stripped = buffer.to_vec();
+    for (pointer, ifd) in tiff.ifds()? {
+        let entries = tiff.entries(ifd)?;
+        let (removed, kept): (Vec<&IfdEntry>, Vec<&IfdEntry>) = entries
+            .iter()
+            .partition(|entry| pointer.is_some() || TIFF_METADATA_TAGS.contains(&entry.tag));
+        for entry in removed {
+            // a value of 4 bytes or less is stored in the entry itself
+            if entry.value.start != entry.pos + 8 {
+                stripped[entry.value.clone()].fill(0);
+            }
+        }
+        let end = ifd + 2 + 12 * entries.len() + 4;
+        if end > buffer.len() {
+            return Err(StructureError::Truncated);
+        }
+        let mut rewritten = tiff.u16_bytes(kept.len() as u16).to_vec();
+        for entry in kept {
+            rewritten.extend_from_slice(&buffer[entry.pos..entry.pos + 12]);
+        }
+        // offset of the next IFD
+        rewritten.extend_from_slice(&buffer[end - 4..end]);
+        rewritten.resize(end - ifd, 0);
+        stripped[ifd..end].copy_from_slice(&rewritten);
+    }
+    Ok(stripped)
+}
+
+// an entry of an IFD : its tag, its position and the position of its value
+struct IfdEntry {
+    tag: u16,
+    pos: usize,
+    value: Range<usize>,
+}
+
+// a TIFF structure, used by the TIFF images and the EXIF blocks
+struct Tiff<'a> {
+    buffer: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(buffer: &'a [u8]) -> Result<Self, StructureError> {
+        let little_endian = match buffer.get(0..4) {
+            Some(b"II*\0") => true,
+            Some(b"MM\0*") => false,
+            _ => return Err(StructureError::InvalidSignature),
+        };
+        Ok(Tiff {
+            buffer,
+            little_endian,
+        })
+    }
+
+    fn read_u16(&self, pos: usize) -> Result<u16, StructureError> {
+        match self.little_endian {
+            true => read_u16_le(self.buffer, pos),
+            false => read_u16_be(self.buffer, pos),
+        }
+        .ok_or(StructureError::Truncated)
+    }
+
+    fn read_u32(&self, pos: usize) -> Result<u32, StructureError> {
+        match self.little_endian {
+            true => read_u32_le(self.buffer, pos),
+            false => read_u32_be(self.buffer, pos),
+        }
+        .ok_or(StructureError::Truncated)
+    }
+
+    fn u16_bytes(&self, value: u16) -> [u8; 2] {
+        match self.little_endian {
+            true => value.to_le_bytes(),
+            false => value.to_be_bytes(),
+        }
+    }
+
+    // the entries of the IFD at the given offset
+    fn entries(&self, ifd: usize) -> Result<Vec<IfdEntry>, StructureError> {
+        let count = self.read_u16(ifd)? as usize;
+        (0..count)
+            .map(|i| {
+                let pos = ifd + 2 + 12 * i;
+                let tag = self.read_u16(pos)?;
+                // size in bytes of the type of the value : BYTE, ASCII, SHORT, LONG, RATIONAL, SBYTE, UNDEFINED,
+                // SSHORT, SLONG, SRATIONAL, FLOAT, DOUBLE and IFD
+                let type_size = match self.read_u16(pos + 2)? {
+                    1 | 2 | 6 | 7 => 1,
+                    3 | 8 => 2,
+                    4 | 9 | 11 | 13 => 4,
+                    5 | 10 | 12 => 8,
+                    _ => return Err(StructureError::InvalidSegment { offset: pos }),
+                };
+                let size = type_size * self.read_u32(pos + 4)? as u64;
+                let start = match size {
+                    0..=4 => pos + 8,
+                    _ => self.read_u32(pos + 8)? as usize,
+                };
+                let end = start as u64 + size;
+                if end > self.buffer.len() as u64 {
+                    return Err(StructureError::Truncated);
+                }
+                Ok(IfdEntry {
+                    tag,
+                    pos,
+                    value: start..end as usize,
+                })
+            })
+            .collect()
+    }
+
+    // The offsets of the IFDs : the chain of IFDs of the images, then the sub-IFDs (EXIF, GPS, interoperability)
+    // with the tag of the entry pointing to them.
+    fn ifds(&self) -> Result<Vec<(Option<u16>, usize)>, StructureError> {
+        let mut ifds: Vec<(Option<u16>, usize)> = Vec::new();
+        let push = |ifds: &mut Vec<(Option<u16>, usize)>, pointer, ifd| {
+            if ifds.len() >= MAX_IFDS || ifds.iter().any(|(_, known)| *known == ifd) {
+                return Err(StructureError::InvalidSegment { offset: ifd });
+            }
+            ifds.push((pointer, ifd));
+            Ok(())
+        };
+        let mut next = self.read_u32(4)? as usize;
+        while next != 0 {
+            push(&mut ifds, None, next)?;
+            let count = self.read_u16(next)? as usize;
+            next = self.read_u32(next + 2 + 12 * count)? as usize;
+        }
+        let mut i = 0;
+        while i < ifds.len() {
+            for entry in self.entries(ifds[i].1)? {
+                if matches!(
+                    entry.tag,
+                    TAG_EXIF_IFD | TAG_GPS_IFD | TAG_INTEROPERABILITY_IFD
+                ) {
+                    let sub_ifd = self.read_u32(entry.value.start)? as usize;
+                    if sub_ifd != 0 {
+                        // the offset of the next IFD, after the entries, must be in the buffer as for the other IFDs
+                        let count = self.read_u16(sub_ifd)? as usize;
+                        self.read_u32(sub_ifd + 2 + 12 * count)?;
+                        push(&mut ifds, Some(entry.tag), sub_ifd)?;
+                    }
+                }
+            }
+            i += 1;
+        }
+        Ok(ifds)
+    }
+}
+
+// the sensitive field of an EXIF or TIFF tag
+fn sensitive_tag(tag: u16) -> Option<SensitiveField> {
+    match tag {
+        // BodySerialNumber, LensSerialNumber, CameraSerialNumber
+        0xA431 | 0xA435 | 0xC62F => Some(SensitiveField::SerialNumber),
+        // ImageUniqueID
+        0xA420 => Some(SensitiveField::UniqueId),
+        // Make, Model, HostComputer, LensMake, LensModel
+        0x010F | 0x0110 | 0x013C | 0xA433 | 0xA434 => Some(SensitiveField::Device),
+        // Artist, XPAuthor, CameraOwnerName
+        0x013B | 0x9C9D | 0xA430 => Some(SensitiveField::Owner),
+        // DateTime, DateTimeOriginal, DateTimeDigitized
+        0x0132 | 0x9003 | 0x9004 => Some(SensitiveField::DateTime),
+        _ => None,
+    }
+}
+
+// EXIF : the tags of all the IFDs. A GPS IFD with at least one entry is a location.
+fn exif_fields(buffer: &[u8], fields: &mut Vec<SensitiveField>) -> Result<(), StructureError> {
+    let tiff = Tiff::new(buffer)?;
+    for (pointer, ifd) in tiff.ifds()? {
+        let entries = tiff.entries(ifd)?;
+        if pointer == Some(TAG_GPS_IFD) {
+            if !entries.is_empty() {
+                fields.push(SensitiveField::Location);
+            }
+            continue;
+        }
+        for entry in entries {
+            match entry.tag {
+                TAG_XMP => xmp_fields(&buffer[entry.value], fields),
+                TAG_IPTC => iptc_fields(&buffer[entry.value], fields),
+                tag => fields.extend(sensitive_tag(tag)),
+            }
+        }
+    }
+    Ok(())
+}
+
+// XMP : the properties are searched in the packet, whatever their syntax (element or attribute)
+fn xmp_fields(packet: &[u8], fields: &mut Vec<SensitiveField>) {
+    let packet = String::from_utf8_lossy(packet);
+    for (properties, field) in [
+        (
+            &[
+                "GPSLatitude",
+                "GPSLongitude",
+                "photoshop:City",
+                "Iptc4xmpCore:Location",
+            ][..],
+            SensitiveField::Location,
+        ),
+        (&["SerialNumber"], SensitiveField::SerialNumber),
+        (
+            &["ImageUniqueID", "xmpMM:DocumentID", "xmpMM:InstanceID"],
+            SensitiveField::UniqueId,
+        ),
+        (
+            &["tiff:Make", "tiff:Model", "LensModel", "aux:Lens"],
+            SensitiveField::Device,
+        ),
+        (
+            &["dc:creator", "tiff:Artist", "OwnerName"],
+            SensitiveField::Owner,
+        ),
+        (
+            &[
+                "DateTimeOriginal",
+                "xmp:CreateDate",
+                "photoshop:DateCreated",
+            ],
+            SensitiveField::DateTime,
+        ),
+    ] {
+        if properties.iter().any(|property| packet.contains(property)) {
+            fields.push(field);
+        }
+    }
+}
+
+// IPTC-IIM : datasets of the application record (tag marker 0x1C, record 2, dataset number)
+fn iptc_fields(buffer: &[u8], fields: &mut Vec<SensitiveField>) {
+    for dataset in buffer.windows(3).filter(|w| w[0] == 0x1C && w[1] == 0x02) {
+        fields.extend(match dataset[2] {
+            // Sub-location, City, Province/State, Country
+            90 | 92 | 95 | 101 => Some(SensitiveField::Location),
+            // By-line
+            80 => Some(SensitiveField::Owner),
+            // Date Created, Digital Creation Date
+            55 | 62 => Some(SensitiveField::DateTime),
+            _ => None,
+        });
+    }
+}
+
+// PNG textual chunks : the keyword tells what the text is
+fn text_fields(payload: &[u8], fields: &mut Vec<SensitiveField>) {
+    let keyword = payload.split(|b| *b == 0).next().unwrap_or_default();
+    fields.extend(match keyword {
+        b"Author" => Some(SensitiveField::Owner),
+        b"Source" => Some(SensitiveField::Device),
+        b"Creation Time" => Some(SensitiveField::DateTime),
+        _ => None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crc32, image_dimensions, image_metadata, strip_metadata, validate_structure, FileValidator,
+        MetadataKind, SensitiveField, StructureError,
+    };
+    use std::fs;
+
+    // A little endian EXIF block : Make in IFD0, BodySerialNumber in the EXIF IFD and a latitude in the GPS IFD.
+    fn exif_block() -> Vec<u8> {
+        let mut tiff = b"II*\0\x08\0\0\0".to_vec();
+        let entry = |tag: u16, value_type: u16, count: u32, value: u32| {
+            [
+                &tag.to_le_bytes()[..],
+                &value_type.to_le_bytes(),
+                &count.to_le_bytes(),
+                &value.to_le_bytes(),
+            ]
+            .concat()
+        };
+        // IFD0 at 8 (3 entries), EXIF IFD at 50 (1 entry), GPS IFD at 68 (1 entry), values at 86
+        tiff.extend_from_slice(&3u16.to_le_bytes());
+        tiff.extend(entry(0x010F, 2, 6, 86));
+        tiff.extend(entry(0x8769, 4, 1, 50));
+        tiff.extend(entry(0x8825, 4, 1, 68));
+        tiff.extend_from_slice(&[0; 4]);
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend(entry(0xA431, 2, 8, 92));
+        tiff.extend_from_slice(&[0; 4]);
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend(entry(0x0001, 2, 2, u32::from_le_bytes(*b"N\0\0\0")));
+        tiff.extend_from_slice(&[0; 4]);
+        tiff.extend_from_slice(b"Canon\0SN123456\0");
+        tiff
+    }
+
+    fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let length = (payload.len() + 2) as u16;
+        [&[0xFF, marker][..], &length.to_be_bytes(), payload].concat()
+    }
+
+    fn png_chunk(chunk_type: &[u8], data: &[u8]) -> Vec<u8> {
+        let body = [chunk_type, data].concat();
+        [
+            &(data.len() as u32).to_be_bytes()[..],
+            &body,
+            &crc32(&body).to_be_bytes(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn jpeg_metadata() {
+        let original = fs::read("res/image/file_example_JPG_100kB.jpg").unwrap();
+        assert!(image_metadata(&original, "image/jpeg")
+            .unwrap()
+            .unwrap()
+            .is_empty());
+        let xmp = b"http://ns.adobe.com/xap/1.0/\0<x:xmpmeta><rdf:Description exif:GPSLatitude=\"46,46.7N\" xmpMM:DocumentID=\"1\"/></x:xmpmeta>";
+        let image = [
+            &original[..2],
+            &jpeg_segment(0xE1, &[b"Exif\0\0", &exif_block()[..]].concat()),
+            &jpeg_segment(0xE1, xmp),
+            &jpeg_segment(0xFE, b"comment"),
+            &original[2..],
+        ]
+        .concat();
+        let metadata = image_metadata(&image, "image/jpeg").unwrap().unwrap();
+        assert_eq!(
+            metadata.blocks,
+            vec![MetadataKind::Exif, MetadataKind::Xmp, MetadataKind::Text]
+        );
+        assert_eq!(
+            metadata.sensitive,
+            vec![
+                SensitiveField::Location,
+                SensitiveField::SerialNumber,
+                SensitiveField::UniqueId,
+                SensitiveField::Device,
+            ]
+        );
+        assert_eq!(
+            strip_metadata(&image, "image/jpeg").unwrap().unwrap(),
+            original,
+            "Only the metadata segments schould be removed"
+        );
+    }
+
+    #[test]
+    fn png_metadata() {
+        let original = fs::read("res/image/file_example_PNG_500kB.png").unwrap();
+        // the fixture contains a tIME chunk after pHYs
+        let metadata = image_metadata(&original, "image/png").unwrap().unwrap();
+        assert_eq!(metadata.blocks, vec![MetadataKind::Timestamp]);
+        assert_eq!(metadata.sensitive, vec![SensitiveField::DateTime]);
+        let image = [
+            &original[..33],
+            &png_chunk(b"eXIf", &exif_block()),
+            &png_chunk(b"tEXt", b"Author\0Axel"),
+            &original[33..],
+        ]
+        .concat();
+        let metadata = image_metadata(&image, "image/png").unwrap().unwrap();
+        assert_eq!(metadata.blocks.len(), 3);
+        assert!(metadata.sensitive.contains(&SensitiveField::Owner));
+        assert!(metadata.sensitive.contains(&SensitiveField::Location));
+        let stripped = strip_metadata(&image, "image/png").unwrap().unwrap();
+        assert_eq!(stripped.len(), original.len() - 19);
+        assert!(image_metadata(&stripped, "image/png")
+            .unwrap()
+            .unwrap()
+            .is_empty());
+        assert_eq!(validate_structure(&stripped, "image/png"), Ok(()));
+        assert_eq!(
+            image_dimensions(&stripped, "image/png"),
+            image_dimensions(&original, "image/png")
+        );
+    }
+
+    #[test]
+    fn webp_metadata() {
+        let original = fs::read("res/image/file_example_WEBP_50kB.webp").unwrap();
+        let exif = exif_block();
+        let xmp = b"<x:xmpmeta><tiff:Model>X</tiff:Model></x:xmpmeta>";
+        // extended WebP : VP8X with the EXIF and XMP flags, canvas of 1050 x 700
+        let mut vp8x = b"VP8X\x0A\0\0\0\x0C\0\0\0".to_vec();
+        vp8x.extend_from_slice(&[0x19, 0x04, 0x00, 0xBB, 0x02, 0x00]);
+        let mut image = b"RIFF\0\0\0\0WEBP".to_vec();
+        image.extend(vp8x);
+        image.extend_from_slice(&original[12..]);
+        for (chunk_type, data) in [(b"EXIF", &exif[..]), (b"XMP ", &xmp[..])] {
+            image.extend_from_slice(chunk_type);
+            image.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            image.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                image.push(0);
+            }
+        }
+        let size = (image.len() - 8) as u32;
+        image[4..8].copy_from_slice(&size.to_le_bytes());
+        assert_eq!(validate_structure(&image, "image/webp"), Ok(()));
+        let metadata = image_metadata(&image, "image/webp").unwrap().unwrap();
+        assert_eq!(metadata.blocks, vec![MetadataKind::Exif, MetadataKind::Xmp]);
+        let stripped = strip_metadata(&image, "image/webp").unwrap().unwrap();
+        assert_eq!(stripped.len(), original.len() + 18);
+        assert_eq!(stripped[20], 0, "The VP8X flags schould be cleared");
+        assert_eq!(validate_structure(&stripped, "image/webp"), Ok(()));
+        assert!(image_metadata(&stripped, "image/webp")
+            .unwrap()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn tiff_metadata() {
+        let original = fs::read("res/image/file_example_TIFF_1MB.tif").unwrap();
+        // the fixture has a DocumentName tag
+        let metadata = image_metadata(&original, "image/tiff").unwrap().unwrap();
+        assert_eq!(metadata.blocks, vec![MetadataKind::Exif]);
+        assert!(metadata.sensitive.is_empty());
+        let stripped = strip_metadata(&original, "image/tiff").unwrap().unwrap();
+        assert_eq!(stripped.len(), original.len());
+        assert!(image_metadata(&stripped, "image/tiff")
+            .unwrap()
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            image_dimensions(&stripped, "image/tiff"),
+            image_dimensions(&original, "image/tiff")
+        );
+        // the EXIF block is a TIFF structure, with its EXIF and GPS IFDs
+        let exif = exif_block();
+        let metadata = image_metadata(&exif, "image/tiff").unwrap().unwrap();
+        assert_eq!(
+            metadata.sensitive,
+            vec![
+                SensitiveField::Location,
+                SensitiveField::SerialNumber,
+                SensitiveField::Device
+            ]
+        );
+        let stripped = strip_metadata(&exif, "image/tiff").unwrap().unwrap();
+        assert!(!stripped.windows(5).any(|w| w == b"Canon" || w == b"SN123"));
+    }
+
+    #[test]
+    fn validated_file_stripped() {
+        let validated = FileValidator::new()
+            .validate_and_read("res/image/file_example_PNG_500kB.png")
+            .unwrap();
+        assert!(!validated.metadata().unwrap().unwrap().is_empty());
+        let stripped = validated.strip_metadata().unwrap();
+        assert!(stripped.metadata().unwrap().unwrap().is_empty());
+        assert_ne!(stripped.uuid(), validated.uuid());
+        assert_eq!(stripped.info(), validated.info());
+        let video = FileValidator::new()
+            .validate_and_read("res/video/file_example_WEBM_480_900KB.webm")
+            .unwrap();
+        assert_eq!(video.metadata().unwrap(), None);
+        assert_eq!(video.strip_metadata().unwrap().content(), video.content());
+    }
+
+    #[test]
+    fn invalid_images() {
+        assert!(image_metadata(b"\xFF\xD8\xFF\xE1\x00", "image/jpeg").is_err());
+        assert!(strip_metadata(b"II*\0\xFF\0\0\0", "image/tiff").is_err());
+        assert_eq!(image_metadata(b"GIF89a", "image/gif"), Ok(None));
+        // an EXIF sub-IFD without room for the offset of the next IFD
+        let tiff = b"II*\0\x08\0\0\0\x01\0\x69\x87\x04\0\x01\0\0\0\x1A\0\0\0\0\0\0\0\0\0";
+        assert_eq!(tiff.len(), 28);
+        assert_eq!(
+            image_metadata(tiff, "image/tiff"),
+            Err(StructureError::Truncated)
+        );
+        assert_eq!(
+            strip_metadata(tiff, "image/tiff"),
+            Err(StructureError::Truncated)
+        );
+        // an IFD pointing to itself
+        let mut tiff = b"II*\0\x08\0\0\0\0\0\x08\0\0\0".to_vec();
+        assert!(image_metadata(&tiff, "image/tiff").is_err());
+        tiff[10..14].copy_from_slice(&[0; 4]);
+        assert!(image_metadata(&tiff, "image/tiff")
+            .unwrap()
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Typed description of the content detected in a file, so the callers don't have to compare strings.
+
+use crate::{DetectedType, Dimensions, VideoMetadata};
+use infer::{MatcherType, Type};
+use std::fmt;
+
+// Family of content a file belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaKind {
+    Image,
+    Video,
+    Audio,
+    Document,
+    Archive,
+    // applications, fonts, text and custom types of infer
+    Other,
+}
+
+impl MediaKind {
+    // lower case name of the kind, "image" for an image, "video" for a video, etc.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaKind::Image => "image",
+            MediaKind::Video => "video",
+            MediaKind::Audio => "audio",
+            MediaKind::Document => "document",
+            MediaKind::Archive => "archive",
+            MediaKind::Other => "other",
+        }
+    }
+}
+
+impl From<MatcherType> for MediaKind {
+    fn from(matcher_type: MatcherType) -> Self {
+        match matcher_type {
+            MatcherType::Image => MediaKind::Image,
+            MatcherType::Video => MediaKind::Video,
+            MatcherType::Audio => MediaKind::Audio,
+            MatcherType::Doc | MatcherType::Book => MediaKind::Document,
+            MatcherType::Archive => MediaKind::Archive,
+            MatcherType::App | MatcherType::Font | MatcherType::Text | MatcherType::Custom => {
+                MediaKind::Other
+            }
+        }
+    }
+}
+
+impl fmt::Display for MediaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// What has been detected in the content of an accepted file.
+// kind : the family of the content
+// mime_type : the MIME type detected with the magic number, e.g. "image/png"
+// extension : the canonical extension of this MIME type, without the dot, e.g. "png"
+// dimensions : the dimensions declared in the header of an image or a video, None for other kinds or unsupported formats
+// video : the metadata read from the container of a video, None for other kinds or unsupported formats
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub kind: MediaKind,
+    pub mime_type: &'static str,
+    pub extension: &'static str,
+    pub dimensions: Option<Dimensions>,
+    pub video: Option<VideoMetadata>,
+}
+
+impl From<DetectedType> for MediaInfo {
+    fn from(detected: DetectedType) -> Self {
+        MediaInfo {
+            kind: detected.matcher_type.into(),
+            mime_type: detected.mime_type,
+            extension: detected.extension,
+            dimensions: None,
+            video: None,
+        }
+    }
+}
+
+impl From<Type> for MediaInfo {
+    fn from(file_type: Type) -> Self {
+        DetectedType::from(file_type).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MediaInfo, MediaKind};
+    use infer::MatcherType;
+
+    #[test]
+    fn kind_from_matcher_type() {
+        assert_eq!(MediaKind::from(MatcherType::Image), MediaKind::Image);
+        assert_eq!(MediaKind::from(MatcherType::Video), MediaKind::Video);
+        assert_eq!(MediaKind::from(MatcherType::Doc), MediaKind::Document);
+        assert_eq!(MediaKind::from(MatcherType::Book), MediaKind::Document);
+        assert_eq!(MediaKind::from(MatcherType::Font), MediaKind::Other);
+    }
+
+    #[test]
+    fn info_from_infer_type() {
+        let file_type = infer::get(&[0xFF, 0xD8, 0xFF, 0xAA]).unwrap();
+        assert_eq!(
+            MediaInfo::from(file_type),
+            MediaInfo {
+                kind: MediaKind::Image,
+                mime_type: "image/jpeg",
+                extension: "jpg",
+                dimensions: None,
+                video: None,
+            }
+        );
+        assert_eq!(MediaKind::Image.to_string(), "image");
+    }
+}
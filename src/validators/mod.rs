@@ -1,7 +1,53 @@
+mod detect_homograph;
+mod detect_polyglot;
+mod detect_type;
+mod file_extension;
+mod file_validator;
+mod image_dimensions;
+mod image_metadata;
+mod media_info;
+mod parse_url;
+mod public_suffix;
+mod sandboxed_file_validator;
+mod sanitize_filename;
+mod url_policy;
+mod validate_archive;
+mod validate_csv;
+mod validate_dir;
 mod validate_file;
+mod validate_json;
+mod validate_structure;
+mod validate_svg;
+mod validate_text;
 mod validate_url;
 mod validate_uuid;
+mod validated_file;
+mod video_metadata;
+mod zip_archive;
 
+pub use detect_homograph::*;
+pub use detect_polyglot::*;
+pub use detect_type::*;
+pub use file_extension::*;
+pub use file_validator::*;
+pub use image_dimensions::*;
+pub use image_metadata::*;
+pub use media_info::*;
+pub use parse_url::*;
+pub use public_suffix::*;
+pub use sandboxed_file_validator::*;
+pub use sanitize_filename::*;
+pub use url_policy::*;
+pub use validate_archive::*;
+pub use validate_csv::*;
+pub use validate_dir::*;
 pub use validate_file::*;
+pub use validate_json::*;
+pub use validate_structure::*;
+pub use validate_svg::*;
+pub use validate_text::*;
 pub use validate_url::*;
 pub use validate_uuid::*;
+pub use validated_file::*;
+pub use video_metadata::*;
+pub use zip_archive::*;
@@ -0,0 +1,180 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Public Suffix List (https://publicsuffix.org), to find the public suffix and the registrable domain of a host.
+
+use crate::idna_forms;
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Error;
+use std::path::Path;
+
+lazy_static! {
+    // the list embedded at build time, to update it replace the file by https://publicsuffix.org/list/public_suffix_list.dat
+    static ref EMBEDDED: PublicSuffixList =
+        PublicSuffixList::parse(include_str!("../../res/public_suffix/public_suffix_list.dat"));
+}
+
+// Rules of a Public Suffix List, in A-labels. The ICANN and the private domains are both used.
+#[derive(Debug, Clone, Default)]
+pub struct PublicSuffixList {
+    rules: HashSet<String>,      // "co.uk"
+    wildcards: HashSet<String>,  // "*.ck", stored without "*."
+    exceptions: HashSet<String>, // "!www.ck", stored without '!'
+}
+
+impl PublicSuffixList {
+    // The list embedded in the library.
+    pub fn embedded() -> &'static PublicSuffixList {
+        &EMBEDDED
+    }
+
+    // Parse a list in the format of public_suffix_list.dat : one rule per line, the rest of the line after a
+    // space is ignored, as the empty lines and the comments starting with "//".
+    pub fn parse(content: &str) -> PublicSuffixList {
+        let mut list = PublicSuffixList::default();
+        for line in content.lines() {
+            let Some(rule) = line.split_whitespace().next() else {
+                continue;
+            };
+            if rule.starts_with("//") {
+                continue;
+            }
+            let (set, rule) = if let Some(rule) = rule.strip_prefix('!') {
+                (&mut list.exceptions, rule)
+            } else if let Some(rule) = rule.strip_prefix("*.") {
+                (&mut list.wildcards, rule)
+            } else {
+                (&mut list.rules, rule)
+            };
+            // the rules in U-labels are converted like the hosts
+            let rule = idna_forms(rule).map_or_else(|| rule.to_lowercase(), |(ascii, _)| ascii);
+            set.insert(rule);
+        }
+        list
+    }
+
+    // Read and parse a list, see parse.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<PublicSuffixList, Error> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    // The public suffix of the host, like "co.uk" for "www.bbc.co.uk". A host matching no rule has its
+    // last label as public suffix. The host must be in A-labels, its case is ignored.
+    // Return None if the host is empty or has an empty label.
+    pub fn public_suffix<'a>(&self, host: &'a str) -> Option<&'a str> {
+        let host = host.strip_suffix('.').unwrap_or(host);
+        if host.split('.').any(str::is_empty) {
+            return None;
+        }
+        let lower = host.to_ascii_lowercase();
+        // start of each suffix of the host, from the longest (the whole host) to the shortest (the last label)
+        let starts: Vec<usize> = std::iter::once(0)
+            .chain(lower.match_indices('.').map(|(pos, _)| pos + 1))
+            .collect();
+        // an exception rule wins over every other rule, its public suffix is the rule without its first label
+        if let Some(index) = starts
+            .iter()
+            .position(|&start| self.exceptions.contains(&lower[start..]))
+        {
+            return starts.get(index + 1).map(|&start| &host[start..]);
+        }
+        // the longest matching rule prevails, the last label is the public suffix if no rule matches
+        let index = (0..starts.len())
+            .find(|&index| {
+                self.rules.contains(&lower[starts[index]..])
+                    || starts
+                        .get(index + 1)
+                        .is_some_and(|&parent| self.wildcards.contains(&lower[parent..]))
+            })
+            .unwrap_or(starts.len() - 1);
+        let suffix_start = starts[index];
+        Some(&host[suffix_start..])
+    }
+
+    // The registrable domain of the host : its public suffix and the label before it, like "bbc.co.uk" for
+    // "www.bbc.co.uk". Return None if the host is a public suffix itself, like "co.uk".
+    pub fn registrable_domain<'a>(&self, host: &'a str) -> Option<&'a str> {
+        let host = host.strip_suffix('.').unwrap_or(host);
+        let suffix = self.public_suffix(host)?;
+        let prefix = host.strip_suffix(suffix)?.strip_suffix('.')?;
+        let start = prefix.rfind('.').map_or(0, |pos| pos + 1);
+        Some(&host[start..])
+    }
+}
+
+// true if the host is the domain or one of its subdomains, the comparison is done on entire labels and without case.
+// e.g. "www.heig-vd.ch" is in "heig-vd.ch" and "ch", but not in "vd.ch" or "g-vd.ch"
+pub fn is_in_domain(host: &str, domain: &str) -> bool {
+    let host = host.strip_suffix('.').unwrap_or(host).to_ascii_lowercase();
+    let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+    host.strip_suffix(&domain)
+        .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{is_in_domain, PublicSuffixList};
+
+    #[test]
+    fn embedded_list() {
+        let list = PublicSuffixList::embedded();
+        for (host, suffix, registrable) in [
+            ("www.heig-vd.ch", Some("ch"), Some("heig-vd.ch")),
+            ("WWW.BBC.CO.UK", Some("CO.UK"), Some("BBC.CO.UK")),
+            ("co.uk", Some("co.uk"), None),
+            ("heig-vd.ch.", Some("ch"), Some("heig-vd.ch")),
+            ("user.github.io", Some("github.io"), Some("user.github.io")),
+            (
+                "a.b.example.unknowntld",
+                Some("unknowntld"),
+                Some("example.unknowntld"),
+            ),
+            // *.ck and !www.ck
+            ("a.b.ck", Some("b.ck"), Some("a.b.ck")),
+            ("www.ck", Some("ck"), Some("www.ck")),
+            ("sub.www.ck", Some("ck"), Some("www.ck")),
+            // 公司.cn
+            (
+                "shop.xn--55qx5d.cn",
+                Some("xn--55qx5d.cn"),
+                Some("shop.xn--55qx5d.cn"),
+            ),
+            ("", None, None),
+            ("heig..ch", None, None),
+        ] {
+            assert_eq!(list.public_suffix(host), suffix, "{}", host);
+            assert_eq!(list.registrable_domain(host), registrable, "{}", host);
+        }
+    }
+
+    #[test]
+    fn custom_list() {
+        let list = PublicSuffixList::parse(
+            "// comment\n\nch\nvd.ch extra text\n*.city.ch\n!www.city.ch\nbücher.ch\n",
+        );
+        assert_eq!(list.public_suffix("heig.vd.ch"), Some("vd.ch"));
+        assert_eq!(
+            list.public_suffix("a.lausanne.city.ch"),
+            Some("lausanne.city.ch")
+        );
+        assert_eq!(list.registrable_domain("www.city.ch"), Some("www.city.ch"));
+        assert_eq!(
+            list.public_suffix("shop.xn--bcher-kva.ch"),
+            Some("xn--bcher-kva.ch")
+        );
+        assert_eq!(list.public_suffix("heig-vd.com"), Some("com"));
+        assert!(PublicSuffixList::from_file("res/public_suffix/public_suffix_list.dat").is_ok());
+        assert!(PublicSuffixList::from_file("file_that_schould_not_exist.dat").is_err());
+    }
+
+    #[test]
+    fn label_boundaries() {
+        assert!(is_in_domain("www.heig-vd.ch", "heig-vd.ch"));
+        assert!(is_in_domain("www.heig-vd.ch", ".CH"));
+        assert!(is_in_domain("heig-vd.ch", "heig-vd.ch"));
+        assert!(!is_in_domain("evil.notch", ".ch"));
+        assert!(!is_in_domain("www.heig-vd.ch", "g-vd.ch"));
+        assert!(!is_in_domain("ch", "heig-vd.ch"));
+    }
+}
@@ -0,0 +1,284 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : A file validator confined to a root directory, that refuses the paths given by a user that lead outside of it.
+
+use crate::{FileValidationError, FileValidator, MediaInfo, ValidatedFile};
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+
+// Validator that only opens regular files inside its root directory. Relative paths are relative to the root.
+// The paths are canonicalised, thus "..", symbolic links and absolute paths can't be used to escape the root.
+// Directories, devices, FIFOs and sockets are refused. The content is verified with the policy of the FileValidator.
+#[derive(Debug, Clone)]
+pub struct SandboxedFileValidator {
+    root: PathBuf,
+    validator: FileValidator,
+}
+
+impl SandboxedFileValidator {
+    // Create a validator confined to the given directory, with the given policy.
+    // Return an io::Error if the root doesn't exist or is not a directory.
+    pub fn new<P: AsRef<Path>>(root: P, validator: FileValidator) -> Result<Self, Error> {
+        let root = root.as_ref().canonicalize()?;
+        if !root.is_dir() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "the root of the sandbox must be a directory",
+            ));
+        }
+        Ok(SandboxedFileValidator { root, validator })
+    }
+
+    // canonical path of the root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // Return the canonical path of a regular file inside the root.
+    // Return FileValidationError::OutsideRoot if the path leads outside the root, FileValidationError::NotRegularFile
+    // if it is not a regular file and FileValidationError::Unreadable if it doesn't exist.
+    pub fn resolve<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf, FileValidationError> {
+        let path = path.as_ref();
+        let outside_root = || FileValidationError::OutsideRoot(path.to_path_buf());
+        // the path is first verified without accessing the disk, so the existence of files outside the root can't be probed
+        if !lexically_normalize(&self.root.join(path)).starts_with(&self.root) {
+            return Err(outside_root());
+        }
+        // then the symbolic links are resolved
+        let canonical = self.root.join(path).canonicalize()?;
+        if !canonical.starts_with(&self.root) {
+            return Err(outside_root());
+        }
+        // the type is read before opening the file, because opening a FIFO would block
+        if !fs::metadata(&canonical)?.is_file() {
+            return Err(FileValidationError::NotRegularFile(path.to_path_buf()));
+        }
+        Ok(canonical)
+    }
+
+    // Verify the file at the given path, which must be inside the root.
+    // Return the MediaInfo of the file if it is accepted, a FileValidationError with the reason otherwise.
+    pub fn validate<P: AsRef<Path>>(&self, path: P) -> Result<MediaInfo, FileValidationError> {
+        let path = path.as_ref();
+        self.validator
+            .validate_open_file(self.open(path)?, &path.to_string_lossy())
+    }
+
+    // Open the file at the given path once, which must be inside the root, verify it and keep the content that has been verified.
+    // See FileValidator::validate_and_read.
+    pub fn validate_and_read<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<ValidatedFile, FileValidationError> {
+        let path = path.as_ref();
+        self.validator
+            .read_open_file(self.open(path)?, &path.to_string_lossy())
+    }
+
+    fn open(&self, path: &Path) -> Result<File, FileValidationError> {
+        let canonical = self.resolve(path)?;
+        // a component of the path could have been replaced by a symbolic link or a FIFO since the resolution :
+        // open_inside doesn't follow symbolic links nor block, and the type of the opened file is verified again
+        let file = open_inside(&self.root, &canonical)?;
+        if !file.metadata()?.is_file() {
+            return Err(FileValidationError::NotRegularFile(path.to_path_buf()));
+        }
+        Ok(file)
+    }
+}
+
+// Open a canonical path inside the root, one component after the other from the root with openat and O_NOFOLLOW :
+// a component that is a symbolic link makes the opening fail, thus the opened file is always inside the root.
+// The file is opened with O_NONBLOCK, so opening a FIFO doesn't wait for a writer.
+#[cfg(unix)]
+pub(crate) fn open_inside(root: &Path, canonical: &Path) -> Result<File, Error> {
+    use std::ffi::CString;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+
+    let open_at = |dir: libc::c_int, name: &Path, flags: libc::c_int| -> Result<OwnedFd, Error> {
+        let name = CString::new(name.as_os_str().as_bytes())?;
+        // openat only reads the name, a valid C string that lives until the end of the call
+        let fd = unsafe {
+            libc::openat(
+                dir,
+                name.as_ptr(),
+                flags | libc::O_RDONLY | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(Error::last_os_error());
+        }
+        // fd is a new descriptor, owned by nothing else
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    };
+    let relative = canonical
+        .strip_prefix(root)
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "the path is outside the root"))?;
+    let mut components = relative.iter().peekable();
+    let mut current = open_at(libc::AT_FDCWD, root, libc::O_DIRECTORY)?;
+    while let Some(name) = components.next() {
+        let flags = match components.peek() {
+            Some(_) => libc::O_DIRECTORY,
+            None => libc::O_NONBLOCK,
+        };
+        current = open_at(current.as_raw_fd(), Path::new(name), flags)?;
+    }
+    Ok(File::from(current))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn open_inside(_root: &Path, canonical: &Path) -> Result<File, Error> {
+    File::open(canonical)
+}
+
+// remove the "." and ".." components of a path, without resolving the symbolic links
+fn lexically_normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FileValidationError, FileValidator, MediaKind, SandboxedFileValidator};
+
+    const IMAGES_PATH: &str = "res/image";
+
+    fn sandbox() -> SandboxedFileValidator {
+        SandboxedFileValidator::new(IMAGES_PATH, FileValidator::new()).unwrap()
+    }
+
+    #[test]
+    fn file_inside_root() {
+        let sandbox = sandbox();
+        assert_eq!(
+            sandbox.validate("file_example_PNG_500kB.png").unwrap().kind,
+            MediaKind::Image
+        );
+        assert!(sandbox
+            .validate("./../image/file_example_GIF_500kB.gif")
+            .is_ok());
+        let absolute = sandbox.root().join("file_example_JPG_100kB.jpg");
+        assert!(sandbox.validate(absolute).is_ok());
+        assert!(matches!(
+            sandbox.validate("missing.png"),
+            Err(FileValidationError::Unreadable(_))
+        ));
+    }
+
+    #[test]
+    fn path_outside_root() {
+        let sandbox = sandbox();
+        for path in [
+            "../video/file_example_MP4_480_1_5MG.mp4",
+            "../../Cargo.toml",
+            "../../../../../../etc/passwd",
+            "/etc/passwd",
+            "../missing.png",
+        ] {
+            assert!(
+                matches!(
+                    sandbox.validate(path),
+                    Err(FileValidationError::OutsideRoot(_))
+                ),
+                "{} schould be outside the root",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn not_regular_files() {
+        let sandbox = SandboxedFileValidator::new("res", FileValidator::new()).unwrap();
+        assert!(matches!(
+            sandbox.validate("image"),
+            Err(FileValidationError::NotRegularFile(_))
+        ));
+        assert!(matches!(
+            sandbox.validate("."),
+            Err(FileValidationError::NotRegularFile(_))
+        ));
+        assert!(
+            SandboxedFileValidator::new(
+                "res/image/file_example_PNG_500kB.png",
+                FileValidator::new()
+            )
+            .is_err(),
+            "The root schould be a directory"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_special_files() {
+        use super::open_inside;
+        use std::os::unix::fs::symlink;
+        use std::process::Command;
+        use std::{env, fs};
+
+        let devices = SandboxedFileValidator::new("/dev", FileValidator::new()).unwrap();
+        assert!(matches!(
+            devices.validate("null"),
+            Err(FileValidationError::NotRegularFile(_))
+        ));
+
+        let root = env::temp_dir().join(format!("sandboxed_validator_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let image = fs::canonicalize("res/image/file_example_PNG_500kB.png").unwrap();
+        symlink(&image, root.join("escape.png")).unwrap();
+        fs::copy(&image, root.join("inside.png")).unwrap();
+        symlink(root.join("inside.png"), root.join("link.png")).unwrap();
+        let fifo = Command::new("mkfifo")
+            .arg(root.join("fifo.png"))
+            .status()
+            .is_ok_and(|status| status.success());
+
+        let sandbox = SandboxedFileValidator::new(&root, FileValidator::new()).unwrap();
+        assert!(
+            matches!(
+                sandbox.validate("escape.png"),
+                Err(FileValidationError::OutsideRoot(_))
+            ),
+            "A symbolic link to a file outside the root schould be refused"
+        );
+        assert!(
+            sandbox.validate("link.png").is_ok(),
+            "A symbolic link inside the root schould be accepted"
+        );
+        if fifo {
+            assert!(matches!(
+                sandbox.validate("fifo.png"),
+                Err(FileValidationError::NotRegularFile(_))
+            ));
+            // a FIFO that replaced the file after the resolution
+            assert!(
+                open_inside(&sandbox.root, &sandbox.root.join("fifo.png")).is_ok(),
+                "Opening a FIFO schould not block"
+            );
+        }
+        // a symbolic link that replaced the file or a directory after the resolution
+        assert!(
+            open_inside(&sandbox.root, &sandbox.root.join("escape.png")).is_err(),
+            "A symbolic link schould not be followed once the path is resolved"
+        );
+        fs::create_dir(root.join("dir")).unwrap();
+        symlink(image.parent().unwrap(), root.join("dir").join("images")).unwrap();
+        assert!(open_inside(
+            &sandbox.root,
+            &sandbox.root.join("dir/images/file_example_PNG_500kB.png")
+        )
+        .is_err());
+        assert!(open_inside(&sandbox.root, &sandbox.root.join("inside.png")).is_ok());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Sanitisation of the filenames given by the users and generation of safe storage names.
+
+use crate::{validate_uuid, MediaInfo};
+
+// maximum length of a filename on most filesystems, in bytes
+const MAX_FILENAME_LENGTH: usize = 255;
+
+// names reserved by Windows, with or without extension
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+// characters forbidden in a Windows filename, in addition to the path separators and the control characters
+const WINDOWS_FORBIDDEN_CHARS: [char; 7] = ['<', '>', ':', '"', '|', '?', '*'];
+
+// Return a filename that is safe to display and to store, from a filename given by a user.
+// - only the last component of a path is kept ("../../etc/passwd" gives "passwd")
+// - control characters, Unicode bidi overrides, invisible characters and characters forbidden by Windows are removed
+// - leading and trailing dots and spaces are removed (no hidden file, no ".." name)
+// - only the final extension is kept, the other dots are replaced ("shell.php.jpg" gives "shell_php.jpg")
+// - Windows reserved names are prefixed ("CON.jpg" gives "_CON.jpg")
+// - the name is truncated to 255 bytes, keeping its extension
+// Return None if nothing remains of the filename.
+pub fn sanitize_filename(filename: &str) -> Option<String> {
+    let last_component = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = last_component
+        .chars()
+        .filter(|c| !c.is_control() && !is_invisible(*c) && !WINDOWS_FORBIDDEN_CHARS.contains(c))
+        .collect();
+    let cleaned = cleaned.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    let (stem, extension) = match cleaned.rsplit_once('.') {
+        Some((stem, extension)) => (stem, Some(extension)),
+        None => (cleaned, None),
+    };
+    let mut stem = stem
+        .replace('.', "_")
+        .trim_end_matches(|c: char| c == '_' || c.is_whitespace())
+        .to_string();
+    if stem.is_empty() {
+        return None;
+    }
+    if WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(&stem))
+    {
+        stem.insert(0, '_');
+    }
+    let extension = extension.map(|e| format!(".{}", e)).unwrap_or_default();
+    // the extension is kept if the name is too long, the stem is truncated on a character boundary
+    while stem.len() + extension.len() > MAX_FILENAME_LENGTH {
+        stem.pop();
+        if stem.is_empty() {
+            return None;
+        }
+    }
+    Some(stem + &extension)
+}
+
+// Unicode bidi controls (that can reverse "gpj.exe" to display "exe.jpg") and zero-width characters
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{061C}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
+
+// Build a storage name from the UUID of the content and the canonical extension of the detected type, e.g. "<uuid>.png".
+// The name given by the user is never used, so it can't contain anything dangerous.
+// Return None if the UUID is not valid.
+pub fn storage_name(uuid: &str, info: &MediaInfo) -> Option<String> {
+    if !validate_uuid(uuid) {
+        return None;
+    }
+    Some(format!("{}.{}", uuid.to_lowercase(), info.extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sanitize_filename, storage_name, FileValidator};
+
+    #[test]
+    fn valid_filename() {
+        assert_eq!(
+            sanitize_filename("photo.jpg"),
+            Some("photo.jpg".to_string())
+        );
+        assert_eq!(
+            sanitize_filename("my holidays 2022.png"),
+            Some("my holidays 2022.png".to_string())
+        );
+        assert_eq!(
+            sanitize_filename("vidéo.mp4"),
+            Some("vidéo.mp4".to_string())
+        );
+    }
+
+    #[test]
+    fn path_traversal() {
+        assert_eq!(
+            sanitize_filename("../../etc/passwd"),
+            Some("passwd".to_string())
+        );
+        assert_eq!(
+            sanitize_filename("..\\..\\windows\\win.ini"),
+            Some("win.ini".to_string())
+        );
+        assert_eq!(sanitize_filename(".."), None);
+        assert_eq!(sanitize_filename("dir/"), None);
+        assert_eq!(
+            sanitize_filename(".htaccess"),
+            Some("htaccess".to_string()),
+            "Leading dot schould be removed, no hidden file can be created"
+        );
+    }
+
+    #[test]
+    fn dangerous_characters() {
+        assert_eq!(
+            sanitize_filename("photo\u{0}\n\r.jpg"),
+            Some("photo.jpg".to_string())
+        );
+        assert_eq!(
+            sanitize_filename("invoice\u{202E}gpj.exe"),
+            Some("invoicegpj.exe".to_string()),
+            "Bidi override schould be removed"
+        );
+        assert_eq!(
+            sanitize_filename("a<b>c:d\"e|f?g*.png"),
+            Some("abcdefg.png".to_string())
+        );
+        assert_eq!(
+            sanitize_filename("photo.jpg. . "),
+            Some("photo.jpg".to_string())
+        );
+    }
+
+    #[test]
+    fn double_extension() {
+        assert_eq!(
+            sanitize_filename("shell.php.jpg"),
+            Some("shell_php.jpg".to_string())
+        );
+        assert_eq!(
+            sanitize_filename("archive.tar.gz"),
+            Some("archive_tar.gz".to_string())
+        );
+    }
+
+    #[test]
+    fn windows_reserved_names() {
+        assert_eq!(sanitize_filename("CON"), Some("_CON".to_string()));
+        assert_eq!(sanitize_filename("nul.jpg"), Some("_nul.jpg".to_string()));
+        assert_eq!(
+            sanitize_filename("com1.tar.gz"),
+            Some("com1_tar.gz".to_string())
+        );
+        assert_eq!(
+            sanitize_filename("CONSOLE.jpg"),
+            Some("CONSOLE.jpg".to_string())
+        );
+    }
+
+    #[test]
+    fn long_filename() {
+        let filename = format!("{}.jpg", "é".repeat(200));
+        let sanitized = sanitize_filename(&filename).unwrap();
+        assert!(sanitized.len() <= 255);
+        assert!(sanitized.ends_with("é.jpg"));
+    }
+
+    #[test]
+    fn storage_name_from_uuid() {
+        let info = FileValidator::new()
+            .validate("res/image/file_example_TIFF_1MB.tif")
+            .unwrap();
+        assert_eq!(
+            storage_name("936DA01F-9ABD-4D9D-80C7-02AF85C822A8", &info),
+            Some("936da01f-9abd-4d9d-80c7-02af85c822a8.tif".to_string())
+        );
+        assert_eq!(storage_name("../../etc/passwd", &info), None);
+    }
+}
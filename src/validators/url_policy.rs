@@ -0,0 +1,429 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : A configurable URL validator, that allow every endpoint to declare the schemes and domains it accepts.
+
+use crate::{
+    idna_forms, is_in_domain, parse_url, HomographDetector, ParsedUrl, PublicSuffixList,
+    UrlValidationError,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::sync::Arc;
+
+// maximum length of a domain name and of one of its labels (RFC 1035)
+const MAX_HOST_LENGTH: usize = 253;
+const MAX_LABEL_LENGTH: usize = 63;
+// minimum length of the domain name before the top level domain
+const MIN_DOMAIN_LENGTH: usize = 3;
+
+lazy_static! {
+    // an A-label : letters, numbers and hyphens, but not a hyphen at its start or end
+    static ref LABEL: Regex = Regex::new(r"^[a-zA-Z0-9]([-a-zA-Z0-9]*[a-zA-Z0-9])?$").unwrap();
+}
+
+// Policy used to accept or refuse a URL. Build it with UrlPolicy::new() and the builder methods, then call validate(url).
+// By default, only the schemes http and https are accepted, a URL without scheme is accepted, every domain is accepted,
+// the homograph hosts are not detected and the Public Suffix List embedded in the library is used.
+// In every case :
+// -> the scheme, if any, is followed by "://"
+// -> the host is a domain name whose A-labels contain only letters, numbers and hyphens (not at their start or end)
+// -> the top level domain contains only letters (in any script) and is at least 2 characters long, the name before it at least 3
+#[derive(Debug, Clone)]
+pub struct UrlPolicy {
+    schemes: Vec<String>, // if empty, every scheme made of letters and numbers is accepted
+    require_scheme: bool,
+    top_domains: Vec<String>, // if empty, every top level domain is accepted
+    denied_domains: Vec<String>,
+    homographs: Option<HomographDetector>,
+    suffixes: Option<Arc<PublicSuffixList>>, // if None, the embedded list is used
+}
+
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        UrlPolicy {
+            schemes: vec!["http".to_string(), "https".to_string()],
+            require_scheme: false,
+            top_domains: Vec::new(),
+            denied_domains: Vec::new(),
+            homographs: None,
+            suffixes: None,
+        }
+    }
+}
+
+impl UrlPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replace the accepted schemes by the given ones, compared without case.
+    // With an empty slice, every scheme made of letters and numbers is accepted.
+    pub fn schemes(mut self, schemes: &[&str]) -> Self {
+        self.schemes = schemes.iter().map(|s| s.to_ascii_lowercase()).collect();
+        self
+    }
+
+    // Add a scheme to the accepted ones, compared without case.
+    pub fn allow_scheme(mut self, scheme: &str) -> Self {
+        let scheme = scheme.to_ascii_lowercase();
+        if !self.schemes.contains(&scheme) {
+            self.schemes.push(scheme);
+        }
+        self
+    }
+
+    // if true, a URL without scheme like "heig-vd.ch" is refused.
+    pub fn require_scheme(mut self, require_scheme: bool) -> Self {
+        self.require_scheme = require_scheme;
+        self
+    }
+
+    // Restrict the accepted hosts to the ones whose public suffix is one of the given top level domains or ends with it,
+    // on entire labels : ".uk" accepts "bbc.co.uk", ".co.uk" accepts "bbc.co.uk" but not "evil.uk", ".ch" doesn't accept "evil.notch".
+    // A domain that is not a public suffix, like ".heig-vd.ch", accepts itself and its subdomains, like "www.heig-vd.ch".
+    // An invalid top level domain makes every URL refused with InvalidWhitelist.
+    pub fn top_domains(mut self, top_domains: &[&str]) -> Self {
+        self.top_domains = top_domains.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    // Refuse the hosts that are the given domain or one of its subdomains, like "evil.com" for "www.evil.com" but not
+    // for "notevil.com". Can be called many times to build a blacklist.
+    pub fn deny_domain(mut self, domain: &str) -> Self {
+        let domain = domain.trim_start_matches('.');
+        let ascii = idna_forms(domain).map_or_else(|| domain.to_lowercase(), |(ascii, _)| ascii);
+        self.denied_domains.push(ascii);
+        self
+    }
+
+    // Use this Public Suffix List instead of the embedded one, e.g. a more recent one read with PublicSuffixList::from_file.
+    pub fn public_suffix_list(mut self, suffixes: Arc<PublicSuffixList>) -> Self {
+        self.suffixes = Some(suffixes);
+        self
+    }
+
+    // Refuse the URLs whose host is reported by the detector, see HomographDetector.
+    pub fn reject_homographs(mut self, detector: HomographDetector) -> Self {
+        self.homographs = Some(detector);
+        self
+    }
+
+    // Verify the URL. Return its components if it is accepted, a UrlValidationError otherwise.
+    pub fn validate(&self, url: &str) -> Result<ParsedUrl, UrlValidationError> {
+        let parsed = parse_url(url)?;
+        match &parsed.scheme {
+            Some(scheme)
+                if self.schemes.is_empty()
+                    && !scheme.chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                return Err(UrlValidationError::InvalidScheme(scheme.clone()));
+            }
+            Some(scheme)
+                if !self.schemes.is_empty()
+                    && !self.schemes.contains(&scheme.to_ascii_lowercase()) =>
+            {
+                return Err(UrlValidationError::DisallowedScheme(scheme.clone()));
+            }
+            None if self.require_scheme => return Err(UrlValidationError::MissingScheme),
+            _ => {}
+        }
+        let (Some(host), Some(ascii), Some(unicode)) =
+            (&parsed.host, &parsed.host_ascii, &parsed.host_unicode)
+        else {
+            return Err(UrlValidationError::MissingHost);
+        };
+        if !is_valid_domain(ascii, unicode) {
+            return Err(UrlValidationError::InvalidHost(host.clone()));
+        }
+        if !self.top_domains.is_empty() {
+            // check if topdomain name in whitelist are all correct
+            if let Some(top_domain) = self
+                .top_domains
+                .iter()
+                .find(|top_domain| !is_valid_top_domain(top_domain))
+            {
+                return Err(UrlValidationError::InvalidWhitelist(top_domain.clone()));
+            }
+            // check if the public suffix of the host is in one of the whitelisted topdomain,
+            // or if the host is in a whitelisted domain that is not a public suffix
+            let suffixes = self
+                .suffixes
+                .as_deref()
+                .unwrap_or_else(|| PublicSuffixList::embedded());
+            let suffix = suffixes.public_suffix(ascii).unwrap_or(ascii);
+            if !self.top_domains.iter().any(|top_domain| {
+                let domain = top_domain.trim_start_matches('.');
+                match suffixes.public_suffix(domain) == Some(domain) {
+                    true => is_in_domain(suffix, top_domain),
+                    false => is_in_domain(ascii, top_domain),
+                }
+            }) {
+                return Err(UrlValidationError::NotWhitelisted(ascii.clone()));
+            }
+        }
+        if let Some(domain) = self
+            .denied_domains
+            .iter()
+            .find(|domain| is_in_domain(ascii, domain))
+        {
+            return Err(UrlValidationError::Blacklisted(domain.clone()));
+        }
+        if let Some(detector) = &self.homographs {
+            let threats = detector.detect(&parsed);
+            if !threats.is_empty() {
+                return Err(UrlValidationError::Homograph(threats));
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+// A domain name with at least two labels, the last one being a top level domain made of letters.
+// The labels are verified on the A-labels, the length of the name and the top level domain on the U-labels.
+fn is_valid_domain(ascii: &str, unicode: &str) -> bool {
+    let (Some((labels, _)), Some((domain, top_domain))) =
+        (ascii.rsplit_once('.'), unicode.rsplit_once('.'))
+    else {
+        return false;
+    };
+    ascii.len() <= MAX_HOST_LENGTH
+        && domain.chars().count() >= MIN_DOMAIN_LENGTH
+        && top_domain.chars().count() >= 2
+        && top_domain.chars().all(char::is_alphabetic)
+        && labels.split('.').all(is_valid_label)
+}
+
+// A top level domain of the whitelist, like ".ch" or ".heig-vd.ch" : a '.' followed by labels verified like the ones
+// of is_valid_domain, the last one being made of at least 2 letters.
+fn is_valid_top_domain(top_domain: &str) -> bool {
+    let Some(domain) = top_domain.strip_prefix('.') else {
+        return false;
+    };
+    let (labels, top_domain) = match domain.rsplit_once('.') {
+        Some((labels, top_domain)) => (Some(labels), top_domain),
+        None => (None, domain),
+    };
+    domain.len() <= MAX_HOST_LENGTH
+        && top_domain.len() >= 2
+        && top_domain.chars().all(|c| c.is_ascii_alphabetic())
+        && labels.is_none_or(|labels| labels.split('.').all(is_valid_label))
+}
+
+fn is_valid_label(label: &str) -> bool {
+    label.len() <= MAX_LABEL_LENGTH && LABEL.is_match(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        HomographDetector, HomographThreat, PublicSuffixList, UrlParseError, UrlPolicy,
+        UrlValidationError,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn default_schemes() {
+        let policy = UrlPolicy::new();
+        for url in [
+            "http://heig-vd.ch",
+            "https://heig-vd.ch/path",
+            "HTTPS://heig-vd.ch",
+            "hTtP://heig-vd.ch",
+            "heig-vd.ch",
+        ] {
+            assert!(policy.validate(url).is_ok(), "{} schould pass", url);
+        }
+        for url in [
+            "http2://heig-vd.ch",
+            "ftp://heig-vd.ch",
+            "file://heig-vd.ch/etc/passwd",
+            "javascript://heig-vd.ch/%0Aalert(1)",
+        ] {
+            assert!(
+                matches!(
+                    policy.validate(url),
+                    Err(UrlValidationError::DisallowedScheme(_))
+                ),
+                "{} schould be refused by the scheme allowlist",
+                url
+            );
+        }
+        assert_eq!(
+            policy.validate("mailto:axel@heig-vd.ch").unwrap_err(),
+            UrlValidationError::DisallowedScheme("mailto".to_string())
+        );
+    }
+
+    #[test]
+    fn custom_schemes() {
+        let policy = UrlPolicy::new().schemes(&["HTTPS"]).allow_scheme("Svn+Ssh");
+        assert!(policy.validate("https://heig-vd.ch").is_ok());
+        assert!(policy.validate("SVN+SSH://heig-vd.ch").is_ok());
+        assert_eq!(
+            policy.validate("http://heig-vd.ch").unwrap_err(),
+            UrlValidationError::DisallowedScheme("http".to_string())
+        );
+        let any = UrlPolicy::new().schemes(&[]);
+        assert!(any.validate("http2://heig-vd.ch").is_ok());
+        assert_eq!(
+            any.validate("ht-tp://heig-vd.ch").unwrap_err(),
+            UrlValidationError::InvalidScheme("ht-tp".to_string())
+        );
+    }
+
+    #[test]
+    fn required_scheme() {
+        let policy = UrlPolicy::new().require_scheme(true);
+        assert_eq!(
+            policy.validate("heig-vd.ch/path").unwrap_err(),
+            UrlValidationError::MissingScheme
+        );
+        assert_eq!(
+            policy.validate("//heig-vd.ch/path").unwrap_err(),
+            UrlValidationError::MissingScheme
+        );
+        assert!(policy.validate("https://heig-vd.ch/path").is_ok());
+    }
+
+    #[test]
+    fn top_domains() {
+        let policy = UrlPolicy::new().top_domains(&[".ch", ".org"]);
+        assert!(policy.validate("https://heig-vd.CH").is_ok());
+        assert_eq!(
+            policy.validate("https://heig-vd.fr").unwrap_err(),
+            UrlValidationError::NotWhitelisted("heig-vd.fr".to_string())
+        );
+        assert_eq!(
+            UrlPolicy::new()
+                .top_domains(&[".c"])
+                .validate("https://heig-vd.ch")
+                .unwrap_err(),
+            UrlValidationError::InvalidWhitelist(".c".to_string())
+        );
+        for top_domain in [
+            "..ch",
+            ".-.ch",
+            ".heig-.ch",
+            ".heig_vd.ch",
+            "ch",
+            ".ch.",
+            ".c1",
+        ] {
+            assert_eq!(
+                UrlPolicy::new()
+                    .top_domains(&[top_domain])
+                    .validate("https://heig-vd.ch")
+                    .unwrap_err(),
+                UrlValidationError::InvalidWhitelist(top_domain.to_string())
+            );
+        }
+        assert!(UrlPolicy::new()
+            .top_domains(&[".heig-vd.ch", ".co.uk"])
+            .validate("https://www.heig-vd.ch")
+            .is_ok());
+    }
+
+    #[test]
+    fn internationalised_domains() {
+        let policy = UrlPolicy::new().top_domains(&[".ch"]);
+        let parsed = policy.validate("https://bücher.ch/katalog").unwrap();
+        assert_eq!(parsed.host.as_deref(), Some("bücher.ch"));
+        assert_eq!(parsed.host_ascii.as_deref(), Some("xn--bcher-kva.ch"));
+        assert!(policy.validate("https://xn--bcher-kva.ch").is_ok());
+        assert!(UrlPolicy::new().validate("https://bücher.рф").is_ok());
+        assert_eq!(
+            policy.validate("https://bü.ch").unwrap_err(),
+            UrlValidationError::InvalidHost("bü.ch".to_string()),
+            "The name before the top level domain schould be 3 characters long, not its punycode"
+        );
+        assert_eq!(
+            policy.validate("https://xn--abc.ch").unwrap_err(),
+            UrlValidationError::Syntax(UrlParseError::InvalidIdna { offset: 8 })
+        );
+        assert_eq!(
+            UrlPolicy::new()
+                .validate("https://heig-vd.ch\u{3002}com")
+                .unwrap()
+                .host_ascii
+                .as_deref(),
+            Some("heig-vd.ch.com"),
+            "An ideographic full stop schould separate the labels as a dot"
+        );
+    }
+
+    #[test]
+    fn homographs() {
+        let policy =
+            UrlPolicy::new().reject_homographs(HomographDetector::new().protect("paypal.com"));
+        assert!(policy.validate("https://www.paypal.com/signin").is_ok());
+        assert!(policy.validate("https://bücher.ch").is_ok());
+        assert_eq!(
+            policy.validate("https://paypa1.com").unwrap_err(),
+            UrlValidationError::Homograph(vec![HomographThreat::ProtectedDomain {
+                domain: "paypal.com".to_string()
+            }])
+        );
+        assert!(matches!(
+            policy.validate("https://xn--80ak6aa92e.com"),
+            Err(UrlValidationError::Homograph(_))
+        ));
+        assert!(
+            UrlPolicy::new().validate("https://paypa1.com").is_ok(),
+            "The homographs schould be accepted by default"
+        );
+    }
+
+    #[test]
+    fn public_suffixes() {
+        let policy = UrlPolicy::new().top_domains(&[".uk", ".ch"]);
+        assert!(policy.validate("https://www.bbc.co.uk").is_ok());
+        assert_eq!(
+            policy.validate("https://evil.notch").unwrap_err(),
+            UrlValidationError::NotWhitelisted("evil.notch".to_string())
+        );
+        let policy = UrlPolicy::new().top_domains(&[".co.uk"]);
+        assert!(policy.validate("https://www.bbc.co.uk").is_ok());
+        assert_eq!(
+            policy.validate("https://evil.uk").unwrap_err(),
+            UrlValidationError::NotWhitelisted("evil.uk".to_string())
+        );
+        assert_eq!(
+            policy.validate("https://co.uk.evil.com").unwrap_err(),
+            UrlValidationError::NotWhitelisted("co.uk.evil.com".to_string())
+        );
+        let custom = Arc::new(PublicSuffixList::parse("ch\nvd.ch\n"));
+        let policy = UrlPolicy::new()
+            .top_domains(&[".vd.ch"])
+            .public_suffix_list(custom);
+        assert!(policy.validate("https://heig.vd.ch").is_ok());
+        assert!(policy.validate("https://heig-vd.ch").is_err());
+        let policy = UrlPolicy::new().top_domains(&[".heig-vd.ch"]);
+        assert!(policy.validate("https://www.heig-vd.ch").is_ok());
+        assert!(policy.validate("https://heig-vd.ch").is_ok());
+        assert!(policy.validate("https://evil-heig-vd.ch").is_err());
+        assert!(policy.validate("https://heig-vd.ch.evil.com").is_err());
+    }
+
+    #[test]
+    fn denied_domains() {
+        let policy = UrlPolicy::new()
+            .deny_domain("evil.com")
+            .deny_domain(".bücher.ch");
+        for url in [
+            "https://evil.com",
+            "https://www.EVIL.com/path",
+            "https://shop.xn--bcher-kva.ch",
+        ] {
+            assert!(
+                matches!(
+                    policy.validate(url),
+                    Err(UrlValidationError::Blacklisted(_))
+                ),
+                "{} schould be blacklisted",
+                url
+            );
+        }
+        assert!(policy.validate("https://notevil.com").is_ok());
+        assert!(policy.validate("https://evil.com.heig-vd.ch").is_ok());
+    }
+}
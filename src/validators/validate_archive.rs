@@ -0,0 +1,683 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Inspection of a ZIP archive without extracting it on the disk : zip bombs and path traversal are refused, and each entry is verified with a media policy.
+
+use crate::{
+    crc32, read_u16_le, zip_entries, FileValidationError, FileValidator, MediaInfo, StructureError,
+    ZipEntry, DEFAULT_MAX_SIZE,
+};
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{Error, Read};
+use std::path::Path;
+
+const LOCAL_FILE_HEADER: &[u8] = b"PK\x03\x04";
+// size of a local file header without its name and extra field
+const LOCAL_FILE_HEADER_SIZE: usize = 30;
+
+// default limits, for all the entries of the archive and of the nested archives
+const DEFAULT_MAX_ENTRIES: usize = 1000;
+const DEFAULT_MAX_TOTAL_SIZE: u64 = 100 * 1024 * 1024;
+const DEFAULT_MAX_RATIO: u64 = 100;
+const DEFAULT_MAX_DEPTH: usize = 1;
+
+// Reasons why an archive is refused by the validator. entry is the path of the entry in the archive,
+// the path of a nested archive is followed by '/' and the name of the entry in it.
+#[derive(Debug)]
+pub enum ArchiveValidationError {
+    // the file doesn't exist or is not readable
+    Unreadable(Error),
+    // the archive is bigger than the maximum size (in bytes)
+    TooLarge {
+        size: u64,
+        max: u64,
+    },
+    // the central directory or a local header is malformed
+    Structure(StructureError),
+    // the archive (and its nested archives) contain more entries than the maximum
+    TooManyEntries {
+        max: usize,
+    },
+    // the total uncompressed size of the entries exceeds the maximum (in bytes)
+    TotalSizeTooLarge {
+        size: u64,
+        max: u64,
+    },
+    // the compression ratio of this entry exceeds the maximum
+    CompressionRatio {
+        entry: String,
+        ratio: u64,
+        max: u64,
+    },
+    // the name of this entry is absolute or goes up in the hierarchy ("..")
+    UnsafeEntryName(String),
+    // an entry with the same name has already been found, the extraction of this entry would replace it
+    DuplicateEntryName(String),
+    // this entry is encrypted and can't be verified
+    Encrypted(String),
+    // this entry is compressed with a method other than stored (0) or deflate (8)
+    UnsupportedCompression {
+        entry: String,
+        method: u16,
+    },
+    // the content of this entry doesn't match the size or the CRC declared in the archive
+    CorruptedEntry(String),
+    // this nested archive is deeper than the maximum depth
+    TooDeep {
+        entry: String,
+        max: usize,
+    },
+    // this entry is refused by the media policy
+    InvalidEntry {
+        entry: String,
+        error: Box<FileValidationError>,
+    },
+}
+
+impl fmt::Display for ArchiveValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveValidationError::Unreadable(e) => write!(f, "file is not readable: {}", e),
+            ArchiveValidationError::TooLarge { size, max } => write!(
+                f,
+                "file size of {} bytes exceeds the maximum of {} bytes",
+                size, max
+            ),
+            ArchiveValidationError::Structure(e) => write!(f, "invalid archive structure: {}", e),
+            ArchiveValidationError::TooManyEntries { max } => {
+                write!(f, "archive contains more than {} entries", max)
+            }
+            ArchiveValidationError::TotalSizeTooLarge { size, max } => write!(
+                f,
+                "uncompressed size of {} bytes exceeds the maximum of {} bytes",
+                size, max
+            ),
+            ArchiveValidationError::CompressionRatio { entry, ratio, max } => write!(
+                f,
+                "compression ratio of {} for entry {} exceeds the maximum of {}",
+                ratio, entry, max
+            ),
+            ArchiveValidationError::UnsafeEntryName(entry) => {
+                write!(f, "entry name {} is not a safe relative path", entry)
+            }
+            ArchiveValidationError::DuplicateEntryName(entry) => {
+                write!(f, "entry name {} is used more than once", entry)
+            }
+            ArchiveValidationError::Encrypted(entry) => write!(f, "entry {} is encrypted", entry),
+            ArchiveValidationError::UnsupportedCompression { entry, method } => write!(
+                f,
+                "compression method {} of entry {} is not supported",
+                method, entry
+            ),
+            ArchiveValidationError::CorruptedEntry(entry) => {
+                write!(f, "content of entry {} doesn't match its header", entry)
+            }
+            ArchiveValidationError::TooDeep { entry, max } => write!(
+                f,
+                "nested archive {} exceeds the maximum depth of {}",
+                entry, max
+            ),
+            ArchiveValidationError::InvalidEntry { entry, error } => {
+                write!(f, "entry {} is refused: {}", entry, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArchiveValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ArchiveValidationError::Unreadable(e) => Some(e),
+            ArchiveValidationError::Structure(e) => Some(e),
+            ArchiveValidationError::InvalidEntry { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for ArchiveValidationError {
+    fn from(e: Error) -> Self {
+        ArchiveValidationError::Unreadable(e)
+    }
+}
+
+impl From<StructureError> for ArchiveValidationError {
+    fn from(e: StructureError) -> Self {
+        ArchiveValidationError::Structure(e)
+    }
+}
+
+// An entry of an accepted archive.
+// name : the path of the entry, prefixed by the path of its nested archive if any
+// size : the uncompressed size, in bytes
+// info : what has been detected in its content, None for a directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub size: u64,
+    pub info: Option<MediaInfo>,
+}
+
+// The entries of an accepted archive, nested archives included, and their total uncompressed size in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveReport {
+    pub entries: Vec<ArchiveEntry>,
+    pub total_size: u64,
+}
+
+// Policy used to accept a ZIP archive. Build it with ArchiveValidator::new() and the builder methods.
+// The entries are decompressed in memory, one by one, and never written on the disk.
+// By default, the entries must be images or videos accepted by FileValidator::new(), and the nested archives are refused.
+#[derive(Debug, Clone)]
+pub struct ArchiveValidator {
+    policy: FileValidator,
+    max_size: Option<u64>,
+    max_entries: usize,
+    max_total_size: u64,
+    max_ratio: u64,
+    max_depth: usize,
+}
+
+impl Default for ArchiveValidator {
+    fn default() -> Self {
+        ArchiveValidator {
+            policy: FileValidator::new(),
+            max_size: Some(DEFAULT_MAX_SIZE),
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_total_size: DEFAULT_MAX_TOTAL_SIZE,
+            max_ratio: DEFAULT_MAX_RATIO,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+// what has already been counted in the archive and its nested archives
+struct Budget {
+    entries: usize,
+    total_size: u64,
+}
+
+impl ArchiveValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Policy applied to the content of each entry. The name of the entry is used to verify its extension.
+    // To accept nested archives, the policy must accept the archive category.
+    pub fn policy(mut self, policy: FileValidator) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    // Maximum size of the archive file in bytes, DEFAULT_MAX_SIZE by default.
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    // Maximum number of entries, directories and entries of the nested archives included.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    // Maximum total uncompressed size of the entries in bytes, entries of the nested archives included.
+    pub fn max_total_size(mut self, max_total_size: u64) -> Self {
+        self.max_total_size = max_total_size;
+        self
+    }
+
+    // Maximum ratio between the uncompressed and the compressed size of an entry.
+    pub fn max_ratio(mut self, max_ratio: u64) -> Self {
+        self.max_ratio = max_ratio;
+        self
+    }
+
+    // Maximum nesting of archives. 1 means the archive itself, without nested archives.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    // Verify the archive at the given path with this policy.
+    // The size is checked from the metadata, then the file is read with a bounded reader.
+    pub fn validate<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<ArchiveReport, ArchiveValidationError> {
+        let file = File::open(path)?;
+        self.check_size(file.metadata()?.len())?;
+        let mut buffer = Vec::new();
+        match self.max_size {
+            // we read one byte more than the maximum to know if the content is too large
+            Some(max) => file.take(max.saturating_add(1)).read_to_end(&mut buffer)?,
+            None => (&file).read_to_end(&mut buffer)?,
+        };
+        self.validate_bytes(&buffer)
+    }
+
+    // Verify an in-memory archive with this policy.
+    pub fn validate_bytes(&self, buffer: &[u8]) -> Result<ArchiveReport, ArchiveValidationError> {
+        self.check_size(buffer.len() as u64)?;
+        let mut budget = Budget {
+            entries: 0,
+            total_size: 0,
+        };
+        let mut entries = Vec::new();
+        self.validate_archive(buffer, "", 1, &mut budget, &mut entries)?;
+        Ok(ArchiveReport {
+            entries,
+            total_size: budget.total_size,
+        })
+    }
+
+    fn check_size(&self, size: u64) -> Result<(), ArchiveValidationError> {
+        match self.max_size {
+            Some(max) if size > max => Err(ArchiveValidationError::TooLarge { size, max }),
+            _ => Ok(()),
+        }
+    }
+
+    // The limits are checked on the sizes declared by the central directory before anything is decompressed,
+    // then each entry is decompressed up to its declared size and verified.
+    fn validate_archive(
+        &self,
+        buffer: &[u8],
+        prefix: &str,
+        depth: usize,
+        budget: &mut Budget,
+        report: &mut Vec<ArchiveEntry>,
+    ) -> Result<(), ArchiveValidationError> {
+        let entries = zip_entries(buffer)?;
+        budget.entries += entries.len();
+        if budget.entries > self.max_entries {
+            return Err(ArchiveValidationError::TooManyEntries {
+                max: self.max_entries,
+            });
+        }
+        let mut names = HashSet::new();
+        for entry in &entries {
+            let name = format!("{}{}", prefix, entry.name);
+            if !is_safe_entry_name(&entry.name) {
+                return Err(ArchiveValidationError::UnsafeEntryName(name));
+            }
+            if !names.insert(normalize_entry_name(&entry.name)) {
+                return Err(ArchiveValidationError::DuplicateEntryName(name));
+            }
+            budget.total_size = budget.total_size.saturating_add(entry.uncompressed_size);
+            if budget.total_size > self.max_total_size {
+                return Err(ArchiveValidationError::TotalSizeTooLarge {
+                    size: budget.total_size,
+                    max: self.max_total_size,
+                });
+            }
+            let ratio = entry.uncompressed_size / entry.compressed_size.max(1);
+            if ratio > self.max_ratio {
+                return Err(ArchiveValidationError::CompressionRatio {
+                    entry: name,
+                    ratio,
+                    max: self.max_ratio,
+                });
+            }
+        }
+        for entry in entries {
+            let name = format!("{}{}", prefix, entry.name);
+            if entry.is_dir() {
+                report.push(ArchiveEntry {
+                    name,
+                    size: 0,
+                    info: None,
+                });
+                continue;
+            }
+            let content = entry_content(buffer, &entry, &name)?;
+            let info = self
+                .policy
+                .validate_bytes(&content, Some(&entry.name))
+                .map_err(|error| ArchiveValidationError::InvalidEntry {
+                    entry: name.clone(),
+                    error: Box::new(error),
+                })?;
+            let nested = info.mime_type == "application/zip";
+            report.push(ArchiveEntry {
+                name: name.clone(),
+                size: entry.uncompressed_size,
+                info: Some(info),
+            });
+            if nested {
+                if depth >= self.max_depth {
+                    return Err(ArchiveValidationError::TooDeep {
+                        entry: name,
+                        max: self.max_depth,
+                    });
+                }
+                self.validate_archive(&content, &format!("{}/", name), depth + 1, budget, report)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Return the content of the entry, decompressed in memory up to its declared size.
+fn entry_content(
+    buffer: &[u8],
+    entry: &ZipEntry,
+    name: &str,
+) -> Result<Vec<u8>, ArchiveValidationError> {
+    if entry.is_encrypted() {
+        return Err(ArchiveValidationError::Encrypted(name.to_string()));
+    }
+    let header = usize::try_from(entry.local_header_offset).unwrap_or(usize::MAX);
+    if buffer.get(header..header.saturating_add(4)) != Some(LOCAL_FILE_HEADER) {
+        return Err(StructureError::InvalidSegment { offset: header }.into());
+    }
+    let name_length = read_u16_le(buffer, header + 26).unwrap_or_default() as usize;
+    let extra_length = read_u16_le(buffer, header + 28).unwrap_or_default() as usize;
+    let name_start = header + LOCAL_FILE_HEADER_SIZE;
+    let local_name = buffer
+        .get(name_start..name_start + name_length)
+        .ok_or(StructureError::Truncated)?;
+    // an extractor reading the local headers would write the entry under this name, which has not been verified
+    if local_name != entry.name.as_bytes() {
+        return Err(ArchiveValidationError::UnsafeEntryName(name.to_string()));
+    }
+    let data_start = name_start + name_length + extra_length;
+    let data = usize::try_from(entry.compressed_size)
+        .ok()
+        .and_then(|size| buffer.get(data_start..data_start.checked_add(size)?))
+        .ok_or(StructureError::Truncated)?;
+    let size = usize::try_from(entry.uncompressed_size).unwrap_or(usize::MAX);
+    let content = match entry.compression_method {
+        0 => data.to_vec(),
+        // the decompression stops after the declared size, a bigger content is a lie
+        8 => decompress_to_vec_with_limit(data, size)
+            .map_err(|_| ArchiveValidationError::CorruptedEntry(name.to_string()))?,
+        method => {
+            return Err(ArchiveValidationError::UnsupportedCompression {
+                entry: name.to_string(),
+                method,
+            })
+        }
+    };
+    if content.len() != size || crc32(&content) != entry.crc32 {
+        return Err(ArchiveValidationError::CorruptedEntry(name.to_string()));
+    }
+    Ok(content)
+}
+
+// A safe name is a relative path without "." or ".." components, '\' being a separator too.
+// Absolute paths ("/etc/passwd"), drive letters ("C:") and NUL characters are refused.
+pub fn is_safe_entry_name(name: &str) -> bool {
+    let name = name.strip_suffix('/').unwrap_or(name);
+    !name.is_empty()
+        && !name.contains('\0')
+        && !name.contains(':')
+        && name
+            .split(['/', '\\'])
+            .all(|component| !matches!(component, "" | "." | ".."))
+}
+
+// The name under which an extractor could write the entry : '\' is a separator, the trailing '/' of a directory is
+// ignored and the case too, for the case-insensitive file systems.
+fn normalize_entry_name(name: &str) -> String {
+    let name = name.replace('\\', "/");
+    name.strip_suffix('/').unwrap_or(&name).to_lowercase()
+}
+
+// Verify the ZIP archive at the given path with the default policy.
+pub fn validate_archive_file(path: &str) -> Result<ArchiveReport, ArchiveValidationError> {
+    ArchiveValidator::new().validate(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validators::zip_archive::tests::stored_zip;
+    use crate::{
+        crc32, is_safe_entry_name, validate_archive_file, ArchiveValidationError, ArchiveValidator,
+        FileValidationError, FileValidator, MediaKind, DEFAULT_MAX_SIZE,
+    };
+    use infer::MatcherType;
+    use miniz_oxide::deflate::compress_to_vec;
+    use std::fs;
+
+    const PNG: &str = "res/image/file_example_PNG_500kB.png";
+
+    // Build a ZIP archive with a single entry compressed with deflate, declaring the given uncompressed size.
+    fn deflated_zip(name: &str, content: &[u8], declared_size: u32) -> Vec<u8> {
+        let data = compress_to_vec(content, 9);
+        let mut common = Vec::new();
+        common.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        common.extend_from_slice(&0u16.to_le_bytes()); // flags
+        common.extend_from_slice(&8u16.to_le_bytes()); // method
+        common.extend_from_slice(&0u32.to_le_bytes()); // time and date
+        common.extend_from_slice(&crc32(content).to_le_bytes());
+        common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        common.extend_from_slice(&declared_size.to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+        let mut archive = b"PK\x03\x04".to_vec();
+        archive.extend_from_slice(&common);
+        archive.extend_from_slice(name.as_bytes());
+        archive.extend_from_slice(&data);
+        let directory_offset = archive.len() as u32;
+        archive.extend_from_slice(b"PK\x01\x02");
+        archive.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        archive.extend_from_slice(&common);
+        archive.extend_from_slice(&[0; 10]); // comment length, disk, attributes
+        archive.extend_from_slice(&0u32.to_le_bytes()); // local header offset
+        archive.extend_from_slice(name.as_bytes());
+        let directory_size = archive.len() as u32 - directory_offset;
+        archive.extend_from_slice(b"PK\x05\x06\0\0\0\0\x01\0\x01\0");
+        archive.extend_from_slice(&directory_size.to_le_bytes());
+        archive.extend_from_slice(&directory_offset.to_le_bytes());
+        archive.extend_from_slice(&0u16.to_le_bytes());
+        archive
+    }
+
+    #[test]
+    fn documents_archive() {
+        let path = "res/other_extension/zip_2MB.zip";
+        assert!(
+            matches!(
+                validate_archive_file(path),
+                Err(ArchiveValidationError::InvalidEntry { error, .. })
+                    if matches!(*error, FileValidationError::DisallowedCategory(_))
+            ),
+            "The default policy schould only accept images and videos"
+        );
+        let report = ArchiveValidator::new()
+            .policy(FileValidator::new().categories(&[MatcherType::Doc, MatcherType::Archive]))
+            .validate(path)
+            .unwrap();
+        let names: Vec<&str> = report.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "zip_10MB/",
+                "zip_10MB/file_example_ODS_5000.ods",
+                "zip_10MB/file_example_PPT_1MB.ppt",
+                "zip_10MB/file-sample_1MB.doc",
+            ]
+        );
+        assert_eq!(report.total_size, 236789 + 1028608 + 1027072);
+        assert!(report.entries[1..]
+            .iter()
+            .all(|e| e.info.as_ref().unwrap().kind != MediaKind::Image));
+    }
+
+    #[test]
+    fn path_traversal() {
+        let png = fs::read(PNG).unwrap();
+        for name in [
+            "../evil.png",
+            "images/../../evil.png",
+            "/etc/evil.png",
+            "\\server\\evil.png",
+            "images\\..\\..\\evil.png",
+            "C:evil.png",
+            "./evil.png",
+        ] {
+            assert!(
+                matches!(
+                    ArchiveValidator::new().validate_bytes(&stored_zip(&[(name, &png)])),
+                    Err(ArchiveValidationError::UnsafeEntryName(entry)) if entry == name
+                ),
+                "{} schould be refused",
+                name
+            );
+        }
+        // the central directory and the local header don't give the same name
+        let mut archive = stored_zip(&[("image.png", &png)]);
+        archive[30..39].copy_from_slice(b"../ev.png");
+        assert!(
+            matches!(
+                ArchiveValidator::new().validate_bytes(&archive),
+                Err(ArchiveValidationError::UnsafeEntryName(entry)) if entry == "image.png"
+            ),
+            "The name of the local header schould be the one of the central directory"
+        );
+        for names in [
+            ["image.png", "image.png"],
+            ["images/a.png", "images\\a.png"],
+            ["image.png", "IMAGE.PNG"],
+            ["images", "images/"],
+        ] {
+            assert!(
+                matches!(
+                    ArchiveValidator::new()
+                        .validate_bytes(&stored_zip(&[(names[0], &png), (names[1], &png)])),
+                    Err(ArchiveValidationError::DuplicateEntryName(entry)) if entry == names[1]
+                ),
+                "{:?} schould be refused",
+                names
+            );
+        }
+        assert!(is_safe_entry_name("images/2022/photo..png"));
+        assert!(is_safe_entry_name("images/"));
+        assert!(!is_safe_entry_name(""));
+    }
+
+    #[test]
+    fn zip_bombs() {
+        let zeros = vec![0u8; 10 * 1024 * 1024];
+        let bomb = deflated_zip("zeros.png", &zeros, zeros.len() as u32);
+        assert!(matches!(
+            ArchiveValidator::new().validate_bytes(&bomb),
+            Err(ArchiveValidationError::CompressionRatio { max: 100, .. })
+        ));
+        assert!(matches!(
+            ArchiveValidator::new()
+                .max_ratio(u64::MAX)
+                .max_total_size(1024 * 1024)
+                .validate_bytes(&bomb),
+            Err(ArchiveValidationError::TotalSizeTooLarge { .. })
+        ));
+        // the declared size is a lie, the decompression stops after it
+        let liar = deflated_zip("zeros.png", &zeros, 1000);
+        assert!(matches!(
+            ArchiveValidator::new()
+                .max_ratio(u64::MAX)
+                .validate_bytes(&liar),
+            Err(ArchiveValidationError::CorruptedEntry(_))
+        ));
+        let png = fs::read(PNG).unwrap();
+        let archive = stored_zip(&[("a.png", &png), ("b.png", &png)]);
+        assert!(matches!(
+            ArchiveValidator::new()
+                .max_entries(1)
+                .validate_bytes(&archive),
+            Err(ArchiveValidationError::TooManyEntries { max: 1 })
+        ));
+        assert_eq!(
+            ArchiveValidator::new()
+                .validate_bytes(&archive)
+                .unwrap()
+                .total_size,
+            2 * png.len() as u64
+        );
+    }
+
+    #[test]
+    fn default_max_size() {
+        let path =
+            std::env::temp_dir().join(format!("validate_archive_{}.zip", std::process::id()));
+        let file = fs::File::create(&path).unwrap();
+        // a sparse file, nothing is written on the disk
+        file.set_len(DEFAULT_MAX_SIZE + 1).unwrap();
+        let result = ArchiveValidator::new().validate(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            result,
+            Err(ArchiveValidationError::TooLarge {
+                max: DEFAULT_MAX_SIZE,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn compressed_entry() {
+        let png = fs::read(PNG).unwrap();
+        let report = ArchiveValidator::new()
+            .validate_bytes(&deflated_zip("image.png", &png, png.len() as u32))
+            .unwrap();
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(
+            report.entries[0].info.as_ref().unwrap().mime_type,
+            "image/png"
+        );
+        // the extension of the entry is verified by the policy
+        assert!(matches!(
+            ArchiveValidator::new().validate_bytes(&stored_zip(&[("image.jpg", &png)])),
+            Err(ArchiveValidationError::InvalidEntry { error, .. })
+                if matches!(*error, FileValidationError::ExtensionMismatch { .. })
+        ));
+        let mut encrypted = stored_zip(&[("image.png", &png)]);
+        let directory = encrypted
+            .windows(4)
+            .position(|w| w == b"PK\x01\x02")
+            .unwrap();
+        encrypted[directory + 8] |= 1;
+        assert!(matches!(
+            ArchiveValidator::new().validate_bytes(&encrypted),
+            Err(ArchiveValidationError::Encrypted(_))
+        ));
+    }
+
+    #[test]
+    fn nested_archives() {
+        let png = fs::read(PNG).unwrap();
+        let inner = stored_zip(&[("image.png", &png)]);
+        let outer = stored_zip(&[("inner.zip", &inner)]);
+        let policy = FileValidator::new().allow_category(MatcherType::Archive);
+        assert!(matches!(
+            ArchiveValidator::new().validate_bytes(&outer),
+            Err(ArchiveValidationError::InvalidEntry { error, .. })
+                if matches!(*error, FileValidationError::DisallowedCategory(MatcherType::Archive))
+        ));
+        assert!(matches!(
+            ArchiveValidator::new()
+                .policy(policy.clone())
+                .validate_bytes(&outer),
+            Err(ArchiveValidationError::TooDeep { max: 1, .. })
+        ));
+        let report = ArchiveValidator::new()
+            .policy(policy.clone())
+            .max_depth(2)
+            .validate_bytes(&outer)
+            .unwrap();
+        let names: Vec<&str> = report.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["inner.zip", "inner.zip/image.png"]);
+        // the entries of the nested archives are counted with the others
+        assert!(matches!(
+            ArchiveValidator::new()
+                .policy(policy)
+                .max_depth(2)
+                .max_entries(1)
+                .validate_bytes(&outer),
+            Err(ArchiveValidationError::TooManyEntries { max: 1 })
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Parsing of a CSV (RFC 4180) to verify its columns, its limits and to find the cells that could be run as formulas.
+
+use crate::{CellPosition, CsvReport, TextValidationError};
+use std::iter::Peekable;
+use std::str::Chars;
+
+// first characters of a cell that a spreadsheet could interpret as a formula (CSV injection)
+const FORMULA_CHARS: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+// what ends a cell
+#[derive(PartialEq, Eq)]
+enum CellEnd {
+    Delimiter,
+    EndOfRow,
+}
+
+// Parse the CSV and verify that every row has the same number of columns as the first one, within the limits.
+// The cells may be quoted with '"', a quote in a quoted cell is escaped by doubling it. Rows end with LF, CRLF or CR.
+pub fn csv_report(
+    text: &str,
+    delimiter: char,
+    max_rows: Option<usize>,
+    max_columns: Option<usize>,
+) -> Result<CsvReport, TextValidationError> {
+    let mut report = CsvReport {
+        rows: 0,
+        columns: 0,
+        formula_cells: Vec::new(),
+    };
+    let mut chars = text.chars().peekable();
+    while chars.peek().is_some() {
+        let row = report.rows;
+        if let Some(max) = max_rows.filter(|max| row >= *max) {
+            return Err(TextValidationError::TooManyRows { max });
+        }
+        let mut columns = 0;
+        loop {
+            let (cell, end) = read_cell(&mut chars, delimiter, row)?;
+            if is_formula(&cell) {
+                report.formula_cells.push(CellPosition {
+                    row,
+                    column: columns,
+                });
+            }
+            columns += 1;
+            if let Some(max) = max_columns.filter(|max| columns > *max) {
+                return Err(TextValidationError::TooManyColumns { row, max });
+            }
+            if end == CellEnd::EndOfRow {
+                break;
+            }
+        }
+        if row == 0 {
+            report.columns = columns;
+        } else if columns != report.columns {
+            return Err(TextValidationError::InconsistentColumns {
+                row,
+                expected: report.columns,
+                found: columns,
+            });
+        }
+        report.rows += 1;
+    }
+    Ok(report)
+}
+
+// read the content of a cell, unquoted, and what ends it
+fn read_cell(
+    chars: &mut Peekable<Chars>,
+    delimiter: char,
+    row: usize,
+) -> Result<(String, CellEnd), TextValidationError> {
+    let mut cell = String::new();
+    if chars.peek() == Some(&'"') {
+        chars.next();
+        loop {
+            match chars.next() {
+                Some('"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                Some('"') => break,
+                Some(c) => cell.push(c),
+                None => return Err(TextValidationError::CsvSyntax { row }),
+            }
+        }
+        // the closing quote must be followed by the end of the cell
+        return match chars.peek() {
+            None | Some('\n') | Some('\r') => Ok((cell, end_of_row(chars))),
+            Some(c) if *c == delimiter => {
+                chars.next();
+                Ok((cell, CellEnd::Delimiter))
+            }
+            Some(_) => Err(TextValidationError::CsvSyntax { row }),
+        };
+    }
+    loop {
+        match chars.peek() {
+            None | Some('\n') | Some('\r') => return Ok((cell, end_of_row(chars))),
+            Some(c) if *c == delimiter => {
+                chars.next();
+                return Ok((cell, CellEnd::Delimiter));
+            }
+            // a quote is only allowed at the start of a cell
+            Some('"') => return Err(TextValidationError::CsvSyntax { row }),
+            Some(c) => {
+                cell.push(*c);
+                chars.next();
+            }
+        }
+    }
+}
+
+// consume LF, CRLF or CR
+fn end_of_row(chars: &mut Peekable<Chars>) -> CellEnd {
+    if chars.next() == Some('\r') && chars.peek() == Some(&'\n') {
+        chars.next();
+    }
+    CellEnd::EndOfRow
+}
+
+// a number like -12.5 or +1 is not a formula
+fn is_formula(cell: &str) -> bool {
+    cell.starts_with(FORMULA_CHARS) && cell.parse::<f64>().is_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{csv_report, CellPosition, TextValidationError};
+
+    #[test]
+    fn quoted_cells() {
+        let report = csv_report(
+            "id,comment\r\n1,\"a, \"\"quoted\"\"\r\nmultiline\"\r\n2,\"\"\r\n",
+            ',',
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!((report.rows, report.columns), (3, 2));
+        let report = csv_report("a;b\n1;2", ';', None, None).unwrap();
+        assert_eq!((report.rows, report.columns), (2, 2));
+    }
+
+    #[test]
+    fn invalid_syntax() {
+        assert!(matches!(
+            csv_report("a,b\n1,\"unterminated\n", ',', None, None),
+            Err(TextValidationError::CsvSyntax { row: 1 })
+        ));
+        assert!(matches!(
+            csv_report("a,b\n1,ab\"c\n", ',', None, None),
+            Err(TextValidationError::CsvSyntax { row: 1 })
+        ));
+        assert!(matches!(
+            csv_report("a,b\n\"1\"x,2\n", ',', None, None),
+            Err(TextValidationError::CsvSyntax { row: 1 })
+        ));
+        assert!(matches!(
+            csv_report("a,b\n1,2,3\n", ',', None, None),
+            Err(TextValidationError::InconsistentColumns {
+                row: 1,
+                expected: 2,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn limits() {
+        let csv = "a,b,c\n1,2,3\n4,5,6\n";
+        assert!(csv_report(csv, ',', Some(3), Some(3)).is_ok());
+        assert!(matches!(
+            csv_report(csv, ',', Some(2), None),
+            Err(TextValidationError::TooManyRows { max: 2 })
+        ));
+        assert!(matches!(
+            csv_report(csv, ',', None, Some(2)),
+            Err(TextValidationError::TooManyColumns { row: 0, max: 2 })
+        ));
+    }
+
+    #[test]
+    fn formula_cells() {
+        let report = csv_report(
+            "a,b,c,d\n=1+1,+cmd|' /C calc'!A0,@SUM(A1),-12.5\n\"-2+3\",\"\tx\",+7,text\n",
+            ',',
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            report.formula_cells,
+            vec![
+                CellPosition { row: 1, column: 0 },
+                CellPosition { row: 1, column: 1 },
+                CellPosition { row: 1, column: 2 },
+                CellPosition { row: 2, column: 0 },
+                CellPosition { row: 2, column: 1 },
+            ],
+            "Numbers schould not be reported, quoted formulas schould"
+        );
+    }
+}
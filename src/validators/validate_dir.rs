@@ -0,0 +1,334 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Verification of every file of a directory tree with a policy, optionally in parallel, with a report of the accepted and refused files.
+
+use crate::{open_inside, FileValidationError, FileValidator, MediaInfo};
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+// Result of the verification of a file of the tree.
+#[derive(Debug)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub result: Result<MediaInfo, FileValidationError>,
+}
+
+// Results of the verification of a directory tree, sorted by path.
+// A directory that can't be read is reported as an Unreadable file.
+#[derive(Debug)]
+pub struct DirReport {
+    pub files: Vec<FileReport>,
+}
+
+impl DirReport {
+    // the accepted files and what has been detected in them
+    pub fn accepted(&self) -> impl Iterator<Item = (&Path, &MediaInfo)> {
+        self.files
+            .iter()
+            .filter_map(|file| Some((file.path.as_path(), file.result.as_ref().ok()?)))
+    }
+
+    // the refused files and the reason of the refusal
+    pub fn rejected(&self) -> impl Iterator<Item = (&Path, &FileValidationError)> {
+        self.files
+            .iter()
+            .filter_map(|file| Some((file.path.as_path(), file.result.as_ref().err()?)))
+    }
+
+    // true if every file of the tree has been accepted
+    pub fn is_valid(&self) -> bool {
+        self.files.iter().all(|file| file.result.is_ok())
+    }
+}
+
+// Verification of a directory tree with a FileValidator. Build it with DirValidator::new(policy) and the builder methods.
+// The symbolic links are not followed : they are refused as NotRegularFile, like the devices, FIFOs and sockets.
+// The files are opened without following a symbolic link nor waiting on a FIFO, even if they have been replaced
+// since the walk of the tree.
+// By default, the files are verified one by one in the current thread.
+#[derive(Debug, Clone)]
+pub struct DirValidator {
+    policy: FileValidator,
+    threads: usize,
+}
+
+impl DirValidator {
+    pub fn new(policy: FileValidator) -> Self {
+        DirValidator { policy, threads: 1 }
+    }
+
+    // Maximum number of files verified at the same time, each one in its own thread. 0 is handled as 1.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    // Verify every file of the tree under root. The order of the report doesn't depend on the number of threads.
+    // Return an Error only if root can't be read.
+    pub fn validate<P: AsRef<Path>>(&self, root: P) -> Result<DirReport, Error> {
+        let mut files = Vec::new();
+        let mut unreadable = Vec::new();
+        let root = root.as_ref();
+        walk(root, &mut files, &mut unreadable, true)?;
+        files.sort();
+        let mut reports = self.validate_files(root, &root.canonicalize()?, files);
+        reports.extend(unreadable.into_iter().map(|(path, e)| FileReport {
+            path,
+            result: Err(FileValidationError::Unreadable(e)),
+        }));
+        reports.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(DirReport { files: reports })
+    }
+
+    // the workers take the next file of the list until it is empty, the results are stored at the index of their file
+    fn validate_files(
+        &self,
+        root: &Path,
+        canonical_root: &Path,
+        files: Vec<(PathBuf, bool)>,
+    ) -> Vec<FileReport> {
+        let validate = |(path, regular): &(PathBuf, bool)| match regular {
+            true => self.validate_file(root, canonical_root, path),
+            false => Err(FileValidationError::NotRegularFile(path.clone())),
+        };
+        if self.threads == 1 {
+            return files
+                .iter()
+                .map(|file| FileReport {
+                    path: file.0.clone(),
+                    result: validate(file),
+                })
+                .collect();
+        }
+        let next = AtomicUsize::new(0);
+        let results: Vec<Mutex<Option<Result<MediaInfo, FileValidationError>>>> =
+            files.iter().map(|_| Mutex::new(None)).collect();
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(files.len()) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(file) = files.get(index) else {
+                        break;
+                    };
+                    let result = validate(file);
+                    *results[index].lock().unwrap() = Some(result);
+                });
+            }
+        });
+        files
+            .into_iter()
+            .zip(results)
+            .map(|((path, _), result)| FileReport {
+                // every index has been taken by a worker, the panic of a worker is propagated by the scope
+                result: result
+                    .into_inner()
+                    .ok()
+                    .flatten()
+                    .expect("file validated by a worker"),
+                path,
+            })
+            .collect()
+    }
+
+    // Open the file from the canonical root, the entry could have been replaced by a symbolic link or a special file
+    // after the walk : the type is verified on the opened file.
+    fn validate_file(
+        &self,
+        root: &Path,
+        canonical_root: &Path,
+        path: &Path,
+    ) -> Result<MediaInfo, FileValidationError> {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let file = open_inside(canonical_root, &canonical_root.join(relative))?;
+        if !file.metadata()?.is_file() {
+            return Err(FileValidationError::NotRegularFile(path.to_path_buf()));
+        }
+        self.policy
+            .validate_open_file(file, &path.to_string_lossy())
+    }
+}
+
+// Collect the files of the tree, with true for a regular file. The directories that can't be read are collected
+// with their error, except the root whose error is returned.
+fn walk(
+    dir: &Path,
+    files: &mut Vec<(PathBuf, bool)>,
+    unreadable: &mut Vec<(PathBuf, Error)>,
+    root: bool,
+) -> Result<(), Error> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if !root => {
+            unreadable.push((dir.to_path_buf(), e));
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                unreadable.push((dir.to_path_buf(), e));
+                continue;
+            }
+        };
+        let path = entry.path();
+        // the type of the entry itself, a symbolic link is not followed
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => walk(&path, files, unreadable, false)?,
+            Ok(file_type) => files.push((path, file_type.is_file())),
+            Err(e) => unreadable.push((path, e)),
+        }
+    }
+    Ok(())
+}
+
+// Verify every file of the tree under root with the given policy, in the current thread.
+pub fn validate_dir<P: AsRef<Path>>(root: P, policy: &FileValidator) -> Result<DirReport, Error> {
+    DirValidator::new(policy.clone()).validate(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{validate_dir, DirValidator, FileValidationError, FileValidator, MediaKind};
+    use infer::MatcherType;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn resources_tree() {
+        let report = validate_dir("res", &FileValidator::new()).unwrap();
+        let paths: Vec<&Path> = report.files.iter().map(|f| f.path.as_path()).collect();
+        let mut sorted = paths.clone();
+        sorted.sort();
+        assert_eq!(paths, sorted, "The report schould be sorted by path");
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.accepted().count(),
+            fs::read_dir("res/image").unwrap().count() + fs::read_dir("res/video").unwrap().count()
+        );
+        for (path, _) in report.rejected() {
+            assert!(
+                !path.starts_with("res/image") && !path.starts_with("res/video"),
+                "{} schould have been accepted",
+                path.display()
+            );
+        }
+        assert!(report.rejected().any(|(path, e)| path
+            == Path::new("res/other_extension/file_example_MP3_700KB.mp3")
+            && matches!(
+                e,
+                FileValidationError::DisallowedCategory(MatcherType::Audio)
+            )));
+        assert!(report
+            .accepted()
+            .all(|(_, info)| matches!(info.kind, MediaKind::Image | MediaKind::Video)));
+    }
+
+    #[test]
+    fn parallel_report_is_deterministic() {
+        let policy = FileValidator::new().deep_check(true);
+        let sequential = DirValidator::new(policy.clone()).validate("res").unwrap();
+        for threads in [0, 2, 4, 64] {
+            let parallel = DirValidator::new(policy.clone())
+                .threads(threads)
+                .validate("res")
+                .unwrap();
+            assert_eq!(parallel.files.len(), sequential.files.len());
+            for (a, b) in parallel.files.iter().zip(&sequential.files) {
+                assert_eq!(a.path, b.path);
+                assert_eq!(a.result.as_ref().ok(), b.result.as_ref().ok());
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn special_files_not_opened() {
+        let root = std::env::temp_dir().join(format!("validate_dir_{}", std::process::id()));
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::copy(
+            "res/image/file_example_PNG_500kB.png",
+            root.join("nested/image.png"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink("/etc/passwd", root.join("link.png")).unwrap();
+        let fifo = root.join("fifo.png");
+        assert!(std::process::Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap()
+            .success());
+        let report = DirValidator::new(FileValidator::new())
+            .threads(2)
+            .validate(&root)
+            .unwrap();
+        let result: Vec<(String, bool)> = report
+            .files
+            .iter()
+            .map(|f| {
+                let name = f.path.strip_prefix(&root).unwrap();
+                (name.display().to_string(), f.result.is_ok())
+            })
+            .collect();
+        assert_eq!(
+            result,
+            vec![
+                ("fifo.png".to_string(), false),
+                ("link.png".to_string(), false),
+                ("nested/image.png".to_string(), true),
+            ]
+        );
+        assert!(report
+            .rejected()
+            .all(|(_, e)| matches!(e, FileValidationError::NotRegularFile(_))));
+        fs::remove_dir_all(&root).unwrap();
+        assert!(validate_dir(&root, &FileValidator::new()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn entry_replaced_after_walk() {
+        let root =
+            std::env::temp_dir().join(format!("validate_dir_replaced_{}", std::process::id()));
+        fs::create_dir_all(root.join("nested")).unwrap();
+        let canonical = root.canonicalize().unwrap();
+        let validator = DirValidator::new(FileValidator::new());
+        // a regular file during the walk, replaced by a symbolic link, a FIFO or a link to its directory
+        std::os::unix::fs::symlink(
+            fs::canonicalize("res/image/file_example_PNG_500kB.png").unwrap(),
+            root.join("link.png"),
+        )
+        .unwrap();
+        assert!(matches!(
+            validator.validate_file(&root, &canonical, &root.join("link.png")),
+            Err(FileValidationError::Unreadable(_))
+        ));
+        let fifo = root.join("fifo.png");
+        assert!(std::process::Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap()
+            .success());
+        assert!(matches!(
+            validator.validate_file(&root, &canonical, &fifo),
+            Err(FileValidationError::NotRegularFile(_))
+        ));
+        fs::remove_dir(root.join("nested")).unwrap();
+        std::os::unix::fs::symlink(fs::canonicalize("res/image").unwrap(), root.join("nested"))
+            .unwrap();
+        assert!(matches!(
+            validator.validate_file(
+                &root,
+                &canonical,
+                &root.join("nested/file_example_PNG_500kB.png")
+            ),
+            Err(FileValidationError::Unreadable(_))
+        ));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
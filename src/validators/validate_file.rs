@@ -3,8 +3,153 @@
 // Place     : HEIG-VD, Vaud, Switzerland
 // Objective : A library that allow the verification of a file at a given path. It must be an image or a video. Allow to verify the filename extension too.
 
-use infer::Type;
+use crate::{Dimensions, EmbeddedPayload, FileValidator, MediaInfo, StructureError};
+use infer::MatcherType;
+use std::fmt;
 use std::io::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Reasons why a file is refused by the validator.
+#[derive(Debug)]
+pub enum FileValidationError {
+    // the file doesn't exist or is not readable
+    Unreadable(Error),
+    // the content of the file doesn't match any known signature
+    UnknownType,
+    // the content is known but its category is not accepted
+    DisallowedCategory(MatcherType),
+    // the category is accepted but this MIME type is not
+    DisallowedMimeType(String),
+    // the extension in the filename doesn't match the content of the file
+    ExtensionMismatch { declared: String, detected: String },
+    // an extension before the final one is executed by a web server or a system, like "php" in "shell.php.jpg"
+    DangerousExtension(String),
+    // the file is bigger than the maximum size (in bytes)
+    TooLarge { size: u64, max: u64 },
+    // the file is smaller than the minimum size (in bytes)
+    TooSmall { size: u64, min: u64 },
+    // the deep verification found a problem in the structure of the file
+    Structure(StructureError),
+    // secondary payloads have been found after the end of the container
+    Polyglot(Vec<EmbeddedPayload>),
+    // the polyglot detection can't find the end of the container, because its structure is invalid
+    UnknownContainerEnd(StructureError),
+    // the image is bigger than the maximum width, height or number of pixels
+    ImageTooLarge(Dimensions),
+    // dimension limits are set but the dimensions of this image format can't be read
+    UnknownDimensions,
+    // the video is longer than the maximum duration
+    VideoTooLong(Duration),
+    // a track of the video is encoded with a codec that is not allowed
+    DisallowedCodec(String),
+    // video limits are set but the metadata of this video format can't be read
+    UnknownVideoMetadata,
+    // the path (after resolution of the symbolic links) is outside the root directory of the sandbox
+    OutsideRoot(PathBuf),
+    // the path is a directory, a device, a FIFO or a socket
+    NotRegularFile(PathBuf),
+}
+
+impl fmt::Display for FileValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileValidationError::Unreadable(e) => write!(f, "file is not readable: {}", e),
+            FileValidationError::UnknownType => write!(f, "file type is unknown"),
+            FileValidationError::DisallowedCategory(category) => {
+                write!(f, "file category {:?} is not allowed", category)
+            }
+            FileValidationError::DisallowedMimeType(mime_type) => {
+                write!(f, "MIME type {} is not allowed", mime_type)
+            }
+            FileValidationError::ExtensionMismatch { declared, detected } => write!(
+                f,
+                "declared extension \"{}\" doesn't match detected extension \"{}\"",
+                declared, detected
+            ),
+            FileValidationError::DangerousExtension(extension) => {
+                write!(
+                    f,
+                    "filename contains the dangerous extension \"{}\"",
+                    extension
+                )
+            }
+            FileValidationError::TooLarge { size, max } => write!(
+                f,
+                "file size of {} bytes exceeds the maximum of {} bytes",
+                size, max
+            ),
+            FileValidationError::TooSmall { size, min } => write!(
+                f,
+                "file size of {} bytes is below the minimum of {} bytes",
+                size, min
+            ),
+            FileValidationError::Structure(e) => write!(f, "invalid file structure: {}", e),
+            FileValidationError::Polyglot(payloads) => {
+                write!(f, "embedded payloads found :")?;
+                for payload in payloads {
+                    write!(f, " {} at offset {},", payload.signature, payload.offset)?;
+                }
+                Ok(())
+            }
+            FileValidationError::UnknownContainerEnd(e) => {
+                write!(f, "end of the container can't be found: {}", e)
+            }
+            FileValidationError::ImageTooLarge(dimensions) => write!(
+                f,
+                "image of {}x{} pixels exceeds the dimension limits",
+                dimensions.width, dimensions.height
+            ),
+            FileValidationError::UnknownDimensions => {
+                write!(f, "dimensions of the image can't be read")
+            }
+            FileValidationError::VideoTooLong(duration) => write!(
+                f,
+                "video of {:.3} seconds exceeds the maximum duration",
+                duration.as_secs_f64()
+            ),
+            FileValidationError::DisallowedCodec(codec) => {
+                write!(f, "codec {} is not allowed", codec)
+            }
+            FileValidationError::UnknownVideoMetadata => {
+                write!(f, "metadata of the video can't be read")
+            }
+            FileValidationError::OutsideRoot(path) => {
+                write!(
+                    f,
+                    "path {} is outside the allowed directory",
+                    path.display()
+                )
+            }
+            FileValidationError::NotRegularFile(path) => {
+                write!(f, "path {} is not a regular file", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for FileValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileValidationError::Unreadable(e) => Some(e),
+            FileValidationError::Structure(e) => Some(e),
+            FileValidationError::UnknownContainerEnd(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for FileValidationError {
+    fn from(e: Error) -> Self {
+        FileValidationError::Unreadable(e)
+    }
+}
+
+impl From<StructureError> for FileValidationError {
+    fn from(e: StructureError) -> Self {
+        FileValidationError::Structure(e)
+    }
+}
 
 // Function that allow to verify if the path given in "path" is a valid image or a video.
 // path : the relative path to the file we want to check.
@@ -13,40 +158,37 @@ use std::io::Error;
 // Return the following content :
 // -> IO:Error if the file doesn't exist or is not readable
 // -> Ok(None) if the file is not a video or an image
+// -> Ok(None) if the file is bigger than DEFAULT_MAX_SIZE (100 MiB), because the whole content is read and verified
 // -> Ok(Some(&str)) If the file is an image, with the fixed content "image" or "video" associated
+// the path is a &String to keep the signature of the first version of this function
+#[allow(clippy::ptr_arg)]
 pub fn validate_file(path: &String, verify_extension: bool) -> Result<Option<&str>, Error> {
-    let kind = infer::get_from_path(path)?;
-    match kind {
-        Some(file_type) => {
-            // if we don't want to check the extension, we check directely if it's an image or a video
-            if !verify_extension || match_extension(path, &file_type) {
-                match file_type.matcher_type() {
-                    infer::MatcherType::Image => Ok(Some("image")),
-                    infer::MatcherType::Video => Ok(Some("video")),
-                    _ => Ok(None), // not an image nor a video
-                }
-            } else {
-                Ok(None) // extension in filename don't match exension in header
-            }
-        }
-        None => Ok(None), // the extension is not known. thus it is not accepted as an image or a video.
+    match validate_file_detailed(path, verify_extension) {
+        Ok(info) => Ok(Some(info.kind.as_str())),
+        Err(FileValidationError::Unreadable(e)) => Err(e),
+        Err(_) => Ok(None),
     }
 }
 
-// the comparaison is true if the filename in extension is the same as the content of file
-fn match_extension(path: &String, type_file: &Type) -> bool {
-    if path.ends_with(".jpeg") {
-        return type_file.extension() == "jpg";
-    } else if path.ends_with(".tiff") {
-        return type_file.extension() == "tif";
-    }
-    path.to_lowercase().ends_with(type_file.extension())
+// Same verification as validate_file, but the reason of a refusal is given in the error.
+// A file bigger than DEFAULT_MAX_SIZE is refused with TooLarge, without being read.
+// Return the MediaInfo of the file if it is accepted, a FileValidationError otherwise.
+pub fn validate_file_detailed(
+    path: &str,
+    verify_extension: bool,
+) -> Result<MediaInfo, FileValidationError> {
+    FileValidator::new()
+        .verify_extension(verify_extension)
+        .validate(path)
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::validate_file;
+    use crate::{
+        validate_file, validate_file_detailed, FileValidationError, MediaKind, DEFAULT_MAX_SIZE,
+    };
+    use infer::MatcherType;
     use std::fs;
     const IMAGES_PATH: &str = "res/image";
     const VIDEO_PATH: &str = "res/video";
@@ -154,4 +296,78 @@ mod tests {
             "An inexistant file schould generate an Error"
         );
     }
+
+    #[test]
+    fn detailed_error_reasons() {
+        assert!(
+            matches!(
+                validate_file_detailed("file_that_schould_not_exist.txt", true),
+                Err(FileValidationError::Unreadable(_))
+            ),
+            "An inexistant file schould be reported as unreadable"
+        );
+        assert!(
+            matches!(
+                validate_file_detailed("res/other_extension/file_example_CSV_5000.csv", true),
+                Err(FileValidationError::UnknownType)
+            ),
+            "A CSV file has no magic number and schould be reported as unknown"
+        );
+        assert!(
+            matches!(
+                validate_file_detailed("res/other_extension/file_example_MP3_700KB.mp3", true),
+                Err(FileValidationError::DisallowedCategory(MatcherType::Audio))
+            ),
+            "An audio file schould be reported as a disallowed category"
+        );
+        match validate_file_detailed(
+            "res/image_with_modified_extension/file_example_JPG_100kB.png",
+            true,
+        ) {
+            Err(FileValidationError::ExtensionMismatch { declared, detected }) => {
+                assert_eq!(declared, "png");
+                assert_eq!(detected, "jpg");
+            }
+            _ => panic!("A JPG file named .png schould be reported as an extension mismatch"),
+        }
+    }
+
+    #[test]
+    fn file_too_large() {
+        let path = std::env::temp_dir().join(format!("validate_file_{}.png", std::process::id()));
+        let mut content = fs::read("res/image/file_example_PNG_500kB.png").unwrap();
+        content.truncate(4096);
+        fs::write(&path, content).unwrap();
+        // a sparse file, the end is not written on the disk
+        let file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(DEFAULT_MAX_SIZE + 1).unwrap();
+        let path = path.to_str().unwrap().to_string();
+        let simple = validate_file(&path, true);
+        let detailed = validate_file_detailed(&path, true);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            simple.unwrap(),
+            None,
+            "A file bigger than the maximum size schould not pass"
+        );
+        assert!(matches!(
+            detailed,
+            Err(FileValidationError::TooLarge {
+                max: DEFAULT_MAX_SIZE,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn detailed_media_info() {
+        let info = validate_file_detailed("res/image/file_example_PNG_500kB.png", true).unwrap();
+        assert_eq!(info.kind, MediaKind::Image);
+        assert_eq!(info.mime_type, "image/png");
+        assert_eq!(info.extension, "png");
+        let info =
+            validate_file_detailed("res/video/file_example_MOV_480_700kB.mov", true).unwrap();
+        assert_eq!(info.kind, MediaKind::Video);
+        assert_eq!(info.mime_type, "video/quicktime");
+    }
 }
@@ -0,0 +1,290 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Strict parsing of a JSON (RFC 8259) with a limit on the nesting depth, without building the document in memory.
+
+use crate::TextValidationError;
+
+// Verify that the text is a single valid JSON value, whose objects and arrays are not nested deeper than max_depth.
+// Return the maximum depth of the JSON, 0 for a scalar. The recursion of the parser is bounded by max_depth.
+pub fn json_depth(text: &str, max_depth: usize) -> Result<usize, TextValidationError> {
+    let mut parser = JsonParser {
+        buffer: text.as_bytes(),
+        pos: 0,
+        max_depth,
+        depth: 0,
+    };
+    parser.skip_whitespace();
+    parser.parse_value(0)?;
+    parser.skip_whitespace();
+    if parser.pos != parser.buffer.len() {
+        return Err(parser.syntax_error());
+    }
+    Ok(parser.depth)
+}
+
+struct JsonParser<'a> {
+    buffer: &'a [u8],
+    pos: usize,
+    max_depth: usize,
+    // maximum depth reached
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    fn syntax_error(&self) -> TextValidationError {
+        TextValidationError::JsonSyntax { offset: self.pos }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.buffer.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), TextValidationError> {
+        if self.peek() != Some(byte) {
+            return Err(self.syntax_error());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<(), TextValidationError> {
+        match self.peek() {
+            Some(b'{') => self.parse_container(depth, b'}', true),
+            Some(b'[') => self.parse_container(depth, b']', false),
+            Some(b'"') => self.parse_string(),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b't') => self.parse_literal(b"true"),
+            Some(b'f') => self.parse_literal(b"false"),
+            Some(b'n') => self.parse_literal(b"null"),
+            _ => Err(self.syntax_error()),
+        }
+    }
+
+    // an object (members "key": value) or an array (values), separated by commas
+    fn parse_container(
+        &mut self,
+        depth: usize,
+        close: u8,
+        object: bool,
+    ) -> Result<(), TextValidationError> {
+        let depth = depth + 1;
+        if depth > self.max_depth {
+            return Err(TextValidationError::JsonTooDeep {
+                offset: self.pos,
+                max: self.max_depth,
+            });
+        }
+        self.depth = self.depth.max(depth);
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            if object {
+                self.parse_string()?;
+                self.skip_whitespace();
+                self.expect(b':')?;
+                self.skip_whitespace();
+            }
+            self.parse_value(depth)?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                }
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.syntax_error()),
+            }
+        }
+    }
+
+    // control characters must be escaped, a \u escape of a surrogate must be a valid pair
+    fn parse_string(&mut self) -> Result<(), TextValidationError> {
+        self.expect(b'"')?;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                            self.pos += 1
+                        }
+                        Some(b'u') => {
+                            let start = self.pos - 1;
+                            let code = self.parse_unicode_escape()?;
+                            if (0xDC00..=0xDFFF).contains(&code) {
+                                return Err(TextValidationError::JsonSyntax { offset: start });
+                            }
+                            if (0xD800..=0xDBFF).contains(&code) {
+                                if self.buffer.get(self.pos..self.pos + 2) != Some(b"\\u") {
+                                    return Err(TextValidationError::JsonSyntax { offset: start });
+                                }
+                                self.pos += 1;
+                                if !(0xDC00..=0xDFFF).contains(&self.parse_unicode_escape()?) {
+                                    return Err(TextValidationError::JsonSyntax { offset: start });
+                                }
+                            }
+                        }
+                        _ => return Err(self.syntax_error()),
+                    }
+                }
+                Some(0x00..=0x1F) | None => return Err(self.syntax_error()),
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    // "u" followed by 4 hexadecimal digits
+    fn parse_unicode_escape(&mut self) -> Result<u32, TextValidationError> {
+        self.expect(b'u')?;
+        let code = self
+            .buffer
+            .get(self.pos..self.pos + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.syntax_error())?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    // -? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?
+    fn parse_number(&mut self) -> Result<(), TextValidationError> {
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.syntax_error()),
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.expect_digits()?;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            self.expect_digits()?;
+        }
+        Ok(())
+    }
+
+    fn expect_digits(&mut self) -> Result<(), TextValidationError> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return Err(self.syntax_error());
+        }
+        self.skip_digits();
+        Ok(())
+    }
+
+    fn skip_digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &[u8]) -> Result<(), TextValidationError> {
+        if !self.buffer[self.pos..].starts_with(literal) {
+            return Err(self.syntax_error());
+        }
+        self.pos += literal.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{json_depth, TextFormat, TextValidationError, TextValidator};
+
+    #[test]
+    fn valid_json() {
+        for (json, depth) in [
+            ("null", 0),
+            (" -0.5e+10 ", 0),
+            ("\"caf\\u00e9 \\ud83d\\ude00\"", 0),
+            ("[]", 1),
+            ("{\"a\": [1, 2, {\"b\": true}], \"c\": \"\\\"\"}", 3),
+            ("[[[[\"deep\"]]]]\n", 4),
+        ] {
+            assert_eq!(json_depth(json, 64).unwrap(), depth, "{}", json);
+        }
+    }
+
+    #[test]
+    fn invalid_json() {
+        for json in [
+            "",
+            "{",
+            "[1,]",
+            "{\"a\" 1}",
+            "{a: 1}",
+            "01",
+            "1.",
+            "+1",
+            "'single'",
+            "\"tab\tin string\"",
+            "\"\\x\"",
+            "\"\\ud83d\"",
+            "\"\\ude00\"",
+            "nul",
+            "[1] [2]",
+            "NaN",
+        ] {
+            assert!(
+                matches!(
+                    json_depth(json, 64),
+                    Err(TextValidationError::JsonSyntax { .. })
+                ),
+                "{:?} schould not be valid",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn depth_limit() {
+        let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(
+            matches!(
+                json_depth(&deep, 64),
+                Err(TextValidationError::JsonTooDeep {
+                    offset: 64,
+                    max: 64
+                })
+            ),
+            "A deeply nested JSON schould be refused without overflowing the stack"
+        );
+        assert!(matches!(
+            TextValidator::new()
+                .max_depth(1)
+                .validate_bytes(b"{\"a\": {}}", TextFormat::Json),
+            Err(TextValidationError::JsonTooDeep { offset: 6, max: 1 })
+        ));
+        assert_eq!(
+            TextValidator::new()
+                .validate_bytes(b"{\"a\": {}}", TextFormat::Json)
+                .unwrap()
+                .json_depth,
+            Some(2)
+        );
+    }
+}
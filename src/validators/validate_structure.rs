@@ -0,0 +1,882 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Deep verification of the structure of image and video files, beyond the magic number. Truncated, corrupted files and files with data after the end of the container are refused.
+
+use crate::zip_end;
+use std::fmt;
+
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+// GUID of the top level objects of an ASF (WMV) file : header, data, simple index, index, media object index and timecode index
+const ASF_TOP_LEVEL_OBJECTS: [[u8; 16]; 6] = [
+    [
+        0x30, 0x26, 0xB2, 0x75, 0x8E, 0x66, 0xCF, 0x11, 0xA6, 0xD9, 0x00, 0xAA, 0x00, 0x62, 0xCE,
+        0x6C,
+    ],
+    [
+        0x36, 0x26, 0xB2, 0x75, 0x8E, 0x66, 0xCF, 0x11, 0xA6, 0xD9, 0x00, 0xAA, 0x00, 0x62, 0xCE,
+        0x6C,
+    ],
+    [
+        0x90, 0x08, 0x00, 0x33, 0xB1, 0xE5, 0xCF, 0x11, 0x89, 0xF4, 0x00, 0xA0, 0xC9, 0x03, 0x49,
+        0xCB,
+    ],
+    [
+        0xD3, 0x29, 0xE2, 0xD6, 0xDA, 0x35, 0xD1, 0x11, 0x90, 0x34, 0x00, 0xA0, 0xC9, 0x03, 0x49,
+        0xBE,
+    ],
+    [
+        0xF8, 0x03, 0xB1, 0xFE, 0xAD, 0x12, 0x64, 0x4C, 0x84, 0x0F, 0x2A, 0x1D, 0x2F, 0x7A, 0xD4,
+        0x8C,
+    ],
+    [
+        0xD0, 0x3F, 0xB7, 0x3C, 0x4A, 0x0C, 0x03, 0x48, 0x95, 0x3D, 0xED, 0xF7, 0xB6, 0x22, 0x8F,
+        0x0C,
+    ],
+];
+
+// EBML (WebM, Matroska) element IDs : EBML header, Segment, Void and CRC-32
+const EBML_TOP_LEVEL_IDS: [u32; 4] = [0x1A45_DFA3, 0x1853_8067, 0xEC, 0xBF];
+// SeekHead, Info, Tracks, Cues, Cluster, Chapters, Tags, Attachments, Void and CRC-32
+const EBML_SEGMENT_IDS: [u32; 10] = [
+    0x114D_9B74,
+    0x1549_A966,
+    0x1654_AE6B,
+    0x1C53_BB6B,
+    0x1F43_B675,
+    0x1043_A770,
+    0x1254_C367,
+    0x1941_A469,
+    0xEC,
+    0xBF,
+];
+// Timecode, SilentTracks, Position, PrevSize, SimpleBlock, BlockGroup, EncryptedBlock, Void and CRC-32
+const EBML_CLUSTER_IDS: [u32; 9] = [0xE7, 0x5854, 0xA7, 0xAB, 0xA3, 0xA0, 0xAF, 0xEC, 0xBF];
+const EBML_CLUSTER_ID: u32 = 0x1F43_B675;
+
+// maximum number of IFDs read in a TIFF file, to refuse the endless chains
+pub(crate) const MAX_TIFF_IFDS: usize = 64;
+// TIFF tags giving the offsets and the sizes of the image data : strips, tiles and JPEG thumbnail
+const TIFF_DATA_TAGS: [(u16, u16); 3] = [(273, 279), (324, 325), (513, 514)];
+// TIFF tags pointing to other IFDs : SubIFDs, EXIF, GPS and interoperability
+const TIFF_SUB_IFD_TAGS: [u16; 4] = [330, 34665, 34853, 40965];
+
+// Structural problems found in a file. The offsets are in bytes from the start of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructureError {
+    // the file doesn't start with the signature of its format
+    InvalidSignature,
+    // the file ends before the end of the container
+    Truncated,
+    // a mandatory chunk is missing, or is not at its place (PNG, WebP)
+    MissingChunk(&'static str),
+    // the CRC of a PNG chunk doesn't match its content
+    InvalidChunkCrc { chunk: String, offset: usize },
+    // a chunk, segment, block or entry is malformed
+    InvalidSegment { offset: usize },
+    // the size of the RIFF container is not coherent with the file (WebP)
+    InvalidRiffSize { declared: u64, actual: u64 },
+    // there is data after the end of the container
+    TrailingData { offset: usize, length: usize },
+}
+
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StructureError::InvalidSignature => write!(f, "invalid signature"),
+            StructureError::Truncated => write!(f, "file is truncated"),
+            StructureError::MissingChunk(chunk) => write!(f, "missing {} chunk", chunk),
+            StructureError::InvalidChunkCrc { chunk, offset } => {
+                write!(f, "invalid CRC for chunk {} at offset {}", chunk, offset)
+            }
+            StructureError::InvalidSegment { offset } => {
+                write!(f, "malformed structure at offset {}", offset)
+            }
+            StructureError::InvalidRiffSize { declared, actual } => write!(
+                f,
+                "RIFF size of {} bytes doesn't match the file size of {} bytes",
+                declared, actual
+            ),
+            StructureError::TrailingData { offset, length } => write!(
+                f,
+                "{} bytes of data after the end of the container at offset {}",
+                length, offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StructureError {}
+
+// Verify the structure of the content, according to its MIME type, and refuse any data after the end of the container.
+// Supported formats are PNG, JPEG, GIF, WebP, TIFF and ICO images, MP4, MOV, AVI, WebM, Matroska and WMV videos.
+// The other formats are accepted without verification.
+pub fn validate_structure(buffer: &[u8], mime_type: &str) -> Result<(), StructureError> {
+    match container_end(buffer, mime_type)? {
+        Some(end) if end < buffer.len() => Err(StructureError::TrailingData {
+            offset: end,
+            length: buffer.len() - end,
+        }),
+        _ => Ok(()),
+    }
+}
+
+// Parse the container of the content and return the offset where it ends.
+// Return Ok(None) if the format is not supported.
+pub fn container_end(buffer: &[u8], mime_type: &str) -> Result<Option<usize>, StructureError> {
+    let end = match mime_type {
+        "image/png" => png_end(buffer)?,
+        "image/jpeg" => jpeg_end(buffer)?,
+        "image/gif" => gif_end(buffer)?,
+        "image/webp" => webp_end(buffer)?,
+        "image/tiff" => tiff_end(buffer)?,
+        "image/vnd.microsoft.icon" => ico_end(buffer)?,
+        "video/mp4" | "video/x-m4v" | "video/quicktime" => iso_bmff_end(buffer)?,
+        "video/x-msvideo" => avi_end(buffer)?,
+        "video/webm" | "video/x-matroska" => ebml_end(buffer)?,
+        "video/x-ms-wmv" => asf_end(buffer)?,
+        "application/zip"
+        | "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        | "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        | "application/vnd.openxmlformats-officedocument.presentationml.presentation" => {
+            zip_end(buffer)?
+        }
+        "application/pdf" => pdf_end(buffer)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(end))
+}
+
+// PNG : a signature followed by chunks (length, type, data, CRC). IHDR must be the first chunk, IEND the last one.
+fn png_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if !buffer.starts_with(PNG_SIGNATURE) {
+        return Err(StructureError::InvalidSignature);
+    }
+    let mut pos = PNG_SIGNATURE.len();
+    let mut first = true;
+    let mut has_data = false;
+    loop {
+        let length = read_u32_be(buffer, pos).ok_or(StructureError::Truncated)? as usize;
+        let chunk_type = buffer
+            .get(pos + 4..pos + 8)
+            .ok_or(StructureError::Truncated)?;
+        if length > 0x7FFF_FFFF {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+        let data_end = pos + 8 + length;
+        let crc = read_u32_be(buffer, data_end).ok_or(StructureError::Truncated)?;
+        if first && chunk_type != b"IHDR" {
+            return Err(StructureError::MissingChunk("IHDR"));
+        }
+        if crc32(&buffer[pos + 4..data_end]) != crc {
+            return Err(StructureError::InvalidChunkCrc {
+                chunk: String::from_utf8_lossy(chunk_type).to_string(),
+                offset: pos,
+            });
+        }
+        first = false;
+        match chunk_type {
+            b"IDAT" => has_data = true,
+            b"IEND" if !has_data => return Err(StructureError::MissingChunk("IDAT")),
+            b"IEND" => return Ok(data_end + 4),
+            _ => {}
+        }
+        pos = data_end + 4;
+    }
+}
+
+// JPEG : SOI marker, segments, entropy-coded data after each SOS segment, and EOI marker.
+fn jpeg_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if !buffer.starts_with(&[0xFF, 0xD8]) {
+        return Err(StructureError::InvalidSignature);
+    }
+    let mut pos = 2;
+    loop {
+        let marker = *buffer.get(pos + 1).ok_or(StructureError::Truncated)?;
+        if buffer[pos] != 0xFF {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+        match marker {
+            // fill byte before a marker
+            0xFF => pos += 1,
+            // EOI
+            0xD9 => return Ok(pos + 2),
+            // markers without length (TEM and RSTn)
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            0x00 | 0xD8 => return Err(StructureError::InvalidSegment { offset: pos }),
+            _ => {
+                let length = read_u16_be(buffer, pos + 2).ok_or(StructureError::Truncated)?;
+                if length < 2 {
+                    return Err(StructureError::InvalidSegment { offset: pos });
+                }
+                pos += 2 + length as usize;
+                if pos > buffer.len() {
+                    return Err(StructureError::Truncated);
+                }
+                if marker == 0xDA {
+                    pos = skip_entropy_coded_data(buffer, pos)?;
+                }
+            }
+        }
+    }
+}
+
+// skip the entropy-coded data of a scan, until the next marker that is not a stuffed byte or a restart marker
+pub(crate) fn skip_entropy_coded_data(
+    buffer: &[u8],
+    mut pos: usize,
+) -> Result<usize, StructureError> {
+    loop {
+        let next = *buffer.get(pos + 1).ok_or(StructureError::Truncated)?;
+        if buffer[pos] == 0xFF && next != 0x00 && !(0xD0..=0xD7).contains(&next) {
+            return Ok(pos);
+        }
+        pos += if buffer[pos] == 0xFF { 2 } else { 1 };
+    }
+}
+
+// GIF : header, logical screen descriptor, optional global color table, blocks, and the trailer byte 0x3B.
+fn gif_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if !buffer.starts_with(b"GIF87a") && !buffer.starts_with(b"GIF89a") {
+        return Err(StructureError::InvalidSignature);
+    }
+    let flags = *buffer.get(10).ok_or(StructureError::Truncated)?;
+    let mut pos = 13 + color_table_size(flags);
+    loop {
+        match *buffer.get(pos).ok_or(StructureError::Truncated)? {
+            // trailer
+            0x3B => return Ok(pos + 1),
+            // extension : introducer, label and sub-blocks
+            0x21 => pos = skip_sub_blocks(buffer, pos + 2)?,
+            // image descriptor, optional local color table, LZW minimum code size and sub-blocks
+            0x2C => {
+                let flags = *buffer.get(pos + 9).ok_or(StructureError::Truncated)?;
+                pos = skip_sub_blocks(buffer, pos + 10 + color_table_size(flags) + 1)?;
+            }
+            _ => return Err(StructureError::InvalidSegment { offset: pos }),
+        }
+    }
+}
+
+pub(crate) fn color_table_size(flags: u8) -> usize {
+    if flags & 0x80 != 0 {
+        3 * (1 << ((flags & 0x07) + 1))
+    } else {
+        0
+    }
+}
+
+pub(crate) fn skip_sub_blocks(buffer: &[u8], mut pos: usize) -> Result<usize, StructureError> {
+    loop {
+        let size = *buffer.get(pos).ok_or(StructureError::Truncated)? as usize;
+        pos += 1 + size;
+        if size == 0 {
+            return Ok(pos);
+        }
+    }
+}
+
+// WebP : a RIFF container whose size must fit in the file, with VP8, VP8L or VP8X as first chunk.
+fn webp_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if buffer.len() < 12 || &buffer[0..4] != b"RIFF" || &buffer[8..12] != b"WEBP" {
+        return Err(StructureError::InvalidSignature);
+    }
+    let declared = read_u32_le(buffer, 4).ok_or(StructureError::Truncated)? as u64;
+    let end = 8 + declared as usize;
+    if end > buffer.len() || declared & 1 != 0 {
+        return Err(StructureError::InvalidRiffSize {
+            declared,
+            actual: buffer.len() as u64 - 8,
+        });
+    }
+    let mut pos = 12;
+    while pos < end {
+        let size = read_u32_le(buffer, pos + 4).ok_or(StructureError::Truncated)? as usize;
+        if pos == 12 && !matches!(&buffer[pos..pos + 4], b"VP8 " | b"VP8L" | b"VP8X") {
+            return Err(StructureError::MissingChunk("VP8"));
+        }
+        // the chunks are padded to an even size
+        pos += 8 + size + (size & 1);
+        if pos > end {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+    }
+    if pos == 12 {
+        return Err(StructureError::MissingChunk("VP8"));
+    }
+    Ok(end)
+}
+
+// ICO : a directory of images, each one with its size and offset in the file.
+fn ico_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if read_u16_le(buffer, 0) != Some(0) || read_u16_le(buffer, 2) != Some(1) {
+        return Err(StructureError::InvalidSignature);
+    }
+    let count = read_u16_le(buffer, 4).ok_or(StructureError::Truncated)? as usize;
+    let directory_end = 6 + 16 * count;
+    if count == 0 {
+        return Err(StructureError::InvalidSegment { offset: 4 });
+    }
+    let mut end = directory_end;
+    for entry in (6..directory_end).step_by(16) {
+        let size = read_u32_le(buffer, entry + 8).ok_or(StructureError::Truncated)? as usize;
+        let offset = read_u32_le(buffer, entry + 12).ok_or(StructureError::Truncated)? as usize;
+        if offset < directory_end || size == 0 {
+            return Err(StructureError::InvalidSegment { offset: entry });
+        }
+        if offset + size > buffer.len() {
+            return Err(StructureError::Truncated);
+        }
+        end = end.max(offset + size);
+    }
+    Ok(end)
+}
+
+// TIFF : a header, then a chain of IFDs (one per page) whose entries point to their values, to the image data
+// and to other IFDs. The file ends after the last of these structures.
+fn tiff_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    let little_endian = tiff_byte_order(buffer)?;
+    let read_u16 = |pos| tiff_read_u16(buffer, little_endian, pos).ok_or(StructureError::Truncated);
+    let read_u32 = |pos| tiff_read_u32(buffer, little_endian, pos).ok_or(StructureError::Truncated);
+    let check_end = |start: u64, size: u64| match start + size {
+        end if end > buffer.len() as u64 => Err(StructureError::Truncated),
+        end => Ok(end as usize),
+    };
+    let mut ifds = tiff_ifds(buffer)?;
+    let mut end = 8;
+    let mut i = 0;
+    while i < ifds.len() {
+        let ifd = ifds[i];
+        i += 1;
+        let count = read_u16(ifd)? as usize;
+        end = end.max(ifd + 2 + 12 * count + 4);
+        // values of the SHORT, LONG and IFD entries, by tag
+        let mut values: Vec<(u16, Vec<u64>)> = Vec::new();
+        for pos in (ifd + 2..ifd + 2 + 12 * count).step_by(12) {
+            let tag = read_u16(pos)?;
+            let value_type = read_u16(pos + 2)?;
+            let type_size: u64 = match value_type {
+                1 | 2 | 6 | 7 => 1,
+                3 | 8 => 2,
+                4 | 9 | 11 | 13 => 4,
+                5 | 10 | 12 => 8,
+                _ => return Err(StructureError::InvalidSegment { offset: pos }),
+            };
+            let count = read_u32(pos + 4)? as u64;
+            // a value of 4 bytes or less is stored in the entry itself
+            let start = match type_size * count {
+                0..=4 => pos + 8,
+                size => {
+                    let start = read_u32(pos + 8)? as usize;
+                    end = end.max(check_end(start as u64, size)?);
+                    start
+                }
+            };
+            if matches!(value_type, 3 | 4 | 13) {
+                let read = (0..count as usize)
+                    .map(|index| match value_type {
+                        3 => read_u16(start + 2 * index).map(u64::from),
+                        _ => read_u32(start + 4 * index).map(u64::from),
+                    })
+                    .collect::<Result<Vec<u64>, StructureError>>()?;
+                values.push((tag, read));
+            }
+        }
+        let values_of = |tag: u16| {
+            values
+                .iter()
+                .find(|(known, _)| *known == tag)
+                .map_or(&[][..], |(_, values)| values.as_slice())
+        };
+        for (offsets, sizes) in TIFF_DATA_TAGS {
+            for (offset, size) in values_of(offsets).iter().zip(values_of(sizes)) {
+                end = end.max(check_end(*offset, *size)?);
+            }
+        }
+        for tag in TIFF_SUB_IFD_TAGS {
+            for sub_ifd in values_of(tag).iter().filter(|offset| **offset != 0) {
+                let sub_ifd = *sub_ifd as usize;
+                if ifds.contains(&sub_ifd) || ifds.len() >= MAX_TIFF_IFDS {
+                    return Err(StructureError::InvalidSegment { offset: sub_ifd });
+                }
+                ifds.push(sub_ifd);
+            }
+        }
+    }
+    Ok(end)
+}
+
+// Byte order of a TIFF file : true for little endian ("II"), false for big endian ("MM").
+pub(crate) fn tiff_byte_order(buffer: &[u8]) -> Result<bool, StructureError> {
+    match buffer.get(0..4) {
+        Some(b"II*\0") => Ok(true),
+        Some(b"MM\0*") => Ok(false),
+        _ => Err(StructureError::InvalidSignature),
+    }
+}
+
+pub(crate) fn tiff_read_u16(buffer: &[u8], little_endian: bool, pos: usize) -> Option<u16> {
+    match little_endian {
+        true => read_u16_le(buffer, pos),
+        false => read_u16_be(buffer, pos),
+    }
+}
+
+pub(crate) fn tiff_read_u32(buffer: &[u8], little_endian: bool, pos: usize) -> Option<u32> {
+    match little_endian {
+        true => read_u32_le(buffer, pos),
+        false => read_u32_be(buffer, pos),
+    }
+}
+
+// Offsets of the chain of IFDs of a TIFF file, one per page. An IFD must fit in the buffer, and the chain
+// can't come back to a known IFD or be longer than MAX_TIFF_IFDS.
+pub(crate) fn tiff_ifds(buffer: &[u8]) -> Result<Vec<usize>, StructureError> {
+    let little_endian = tiff_byte_order(buffer)?;
+    let read_u16 = |pos| tiff_read_u16(buffer, little_endian, pos).ok_or(StructureError::Truncated);
+    let read_u32 = |pos| tiff_read_u32(buffer, little_endian, pos).ok_or(StructureError::Truncated);
+    let mut ifds = Vec::new();
+    let mut next = read_u32(4)? as usize;
+    while next != 0 {
+        if ifds.contains(&next) || ifds.len() >= MAX_TIFF_IFDS {
+            return Err(StructureError::InvalidSegment { offset: next });
+        }
+        ifds.push(next);
+        let count = read_u16(next)? as usize;
+        next = read_u32(next + 2 + 12 * count)? as usize;
+    }
+    if ifds.is_empty() {
+        return Err(StructureError::MissingChunk("IFD"));
+    }
+    Ok(ifds)
+}
+
+// MP4, MOV : a sequence of boxes (size, type, content). The container ends at the first data that doesn't look like a box.
+fn iso_bmff_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    Ok(iso_bmff_boxes(buffer)?.last().map_or(0, |(_, end)| *end))
+}
+
+// Type and end offset of each top level box of an MP4 or MOV file, until the first data that doesn't look like a box.
+pub(crate) fn iso_bmff_boxes(buffer: &[u8]) -> Result<Vec<([u8; 4], usize)>, StructureError> {
+    let mut boxes = Vec::new();
+    let mut pos = 0;
+    while pos < buffer.len() {
+        let box_type = match buffer.get(pos + 4..pos + 8) {
+            Some(box_type) if is_box_type(box_type) => box_type,
+            _ if pos == 0 => return Err(StructureError::InvalidSignature),
+            _ => break,
+        };
+        let size = match read_u32_be(buffer, pos).ok_or(StructureError::Truncated)? {
+            // the last box extends to the end of the file
+            0 => (buffer.len() - pos) as u64,
+            // the size is stored on 64 bits after the type
+            1 => read_u64_be(buffer, pos + 8).ok_or(StructureError::Truncated)?,
+            size => size as u64,
+        };
+        if size < 8 || (box_type == b"uuid" && size < 24) {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+        if size > (buffer.len() - pos) as u64 {
+            return Err(StructureError::Truncated);
+        }
+        pos += size as usize;
+        boxes.push((box_type.try_into().unwrap(), pos));
+    }
+    Ok(boxes)
+}
+
+// the type of a box is made of 4 letters, digits or spaces
+fn is_box_type(box_type: &[u8]) -> bool {
+    box_type
+        .iter()
+        .all(|c| c.is_ascii_alphanumeric() || *c == b' ' || *c == 0xA9)
+}
+
+// AVI : one or more consecutive RIFF containers (RIFF AVI followed by RIFF AVIX for files above 1 GB).
+fn avi_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if buffer.get(8..12) != Some(b"AVI ") {
+        return Err(StructureError::InvalidSignature);
+    }
+    let mut pos = 0;
+    while buffer.get(pos..pos + 4) == Some(b"RIFF") {
+        let declared = read_u32_le(buffer, pos + 4).ok_or(StructureError::Truncated)? as u64;
+        let end = pos as u64 + 8 + declared + (declared & 1);
+        if end > buffer.len() as u64 {
+            return Err(StructureError::InvalidRiffSize {
+                declared,
+                actual: (buffer.len() - pos - 8) as u64,
+            });
+        }
+        pos = end as usize;
+    }
+    if pos == 0 {
+        return Err(StructureError::InvalidSignature);
+    }
+    Ok(pos)
+}
+
+// WebM, Matroska : EBML elements. A Segment or a Cluster of unknown size ends at the first element that can't be one of its children.
+fn ebml_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if !buffer.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        return Err(StructureError::InvalidSignature);
+    }
+    ebml_children_end(buffer, 0, &EBML_TOP_LEVEL_IDS)
+}
+
+fn ebml_children_end(buffer: &[u8], mut pos: usize, ids: &[u32]) -> Result<usize, StructureError> {
+    while pos < buffer.len() {
+        let (id, size, data) = match read_ebml_header(buffer, pos) {
+            Some((id, size, data)) if ids.contains(&id) => (id, size, data),
+            _ => break,
+        };
+        pos = match size {
+            Some(size) if size > (buffer.len() - data) as u64 => {
+                return Err(StructureError::Truncated)
+            }
+            Some(size) => data + size as usize,
+            None if id == EBML_CLUSTER_ID => ebml_children_end(buffer, data, &EBML_CLUSTER_IDS)?,
+            None => ebml_children_end(buffer, data, &EBML_SEGMENT_IDS)?,
+        };
+    }
+    Ok(pos)
+}
+
+// read the ID and the size of an EBML element, and return them with the offset of its data.
+// The size is None if it is unknown (all the bits of the value set to 1).
+pub(crate) fn read_ebml_header(buffer: &[u8], pos: usize) -> Option<(u32, Option<u64>, usize)> {
+    let (id, id_length) = read_vint(buffer, pos, 4)?;
+    let (size, size_length) = read_vint(buffer, pos + id_length, 8)?;
+    // the ID keeps its length marker
+    let id = ((id | (1 << (7 * id_length))) & 0xFFFF_FFFF) as u32;
+    let unknown = size == (1 << (7 * size_length)) - 1;
+    Some((
+        id,
+        if unknown { None } else { Some(size) },
+        pos + id_length + size_length,
+    ))
+}
+
+// read a variable size integer of at most max_length bytes, return its value without the length marker and its length
+fn read_vint(buffer: &[u8], pos: usize, max_length: usize) -> Option<(u64, usize)> {
+    let first = *buffer.get(pos)?;
+    let length = first.leading_zeros() as usize + 1;
+    if length > max_length {
+        return None;
+    }
+    let bytes = buffer.get(pos..pos + length)?;
+    let value = bytes[1..]
+        .iter()
+        .fold((first as u64) & (0xFF >> length), |value, &byte| {
+            (value << 8) | byte as u64
+        });
+    Some((value, length))
+}
+
+// WMV : ASF top level objects (GUID, size). The container ends at the first unknown GUID.
+fn asf_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if buffer.get(0..16) != Some(&ASF_TOP_LEVEL_OBJECTS[0]) {
+        return Err(StructureError::InvalidSignature);
+    }
+    let mut pos = 0;
+    while let Some(guid) = buffer.get(pos..pos + 16) {
+        if !ASF_TOP_LEVEL_OBJECTS.iter().any(|known| known == guid) {
+            break;
+        }
+        let size = read_u64_le(buffer, pos + 16).ok_or(StructureError::Truncated)?;
+        if size < 24 {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+        if size > (buffer.len() - pos) as u64 {
+            return Err(StructureError::Truncated);
+        }
+        pos += size as usize;
+    }
+    Ok(pos)
+}
+
+// PDF : the last %%EOF marker ends the file (incremental updates add content and a new marker), followed by an optional end of line
+fn pdf_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if !buffer.starts_with(b"%PDF-") {
+        return Err(StructureError::InvalidSignature);
+    }
+    let marker = buffer
+        .windows(5)
+        .rposition(|window| window == b"%%EOF")
+        .ok_or(StructureError::MissingChunk("%%EOF"))?;
+    let rest = &buffer[marker + 5..];
+    let end_of_line = if rest.starts_with(b"\r\n") {
+        2
+    } else if rest.starts_with(b"\n") || rest.starts_with(b"\r") {
+        1
+    } else {
+        0
+    };
+    Ok(marker + 5 + end_of_line)
+}
+
+pub(crate) fn read_u16_be(buffer: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        buffer.get(pos..pos + 2)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn read_u16_le(buffer: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        buffer.get(pos..pos + 2)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn read_u32_be(buffer: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        buffer.get(pos..pos + 4)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn read_u32_le(buffer: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        buffer.get(pos..pos + 4)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn read_u64_be(buffer: &[u8], pos: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(
+        buffer.get(pos..pos + 8)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn read_u64_le(buffer: &[u8], pos: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        buffer.get(pos..pos + 8)?.try_into().ok()?,
+    ))
+}
+
+// CRC-32 (ISO 3309) used by PNG, with a table computed at compile time
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(0xFFFF_FFFF, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{container_end, validate_structure, StructureError};
+    use std::fs;
+
+    const IMAGES_PATH: &str = "res/image";
+    const VIDEO_PATH: &str = "res/video";
+
+    fn read_image(name: &str) -> Vec<u8> {
+        fs::read(format!("{}/{}", IMAGES_PATH, name)).unwrap()
+    }
+
+    #[test]
+    fn correct_images() {
+        for image_path in fs::read_dir(IMAGES_PATH).unwrap() {
+            let path = image_path.unwrap().path();
+            let buffer = fs::read(&path).unwrap();
+            let file_type = infer::get(&buffer).unwrap();
+            assert_eq!(
+                validate_structure(&buffer, file_type.mime_type()),
+                Ok(()),
+                "{:?} schould have a valid structure",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn correct_videos() {
+        for video_path in fs::read_dir(VIDEO_PATH).unwrap() {
+            let path = video_path.unwrap().path();
+            let buffer = fs::read(&path).unwrap();
+            let file_type = infer::get(&buffer).unwrap();
+            assert_eq!(
+                container_end(&buffer, file_type.mime_type()),
+                Ok(Some(buffer.len())),
+                "{:?} schould have a valid structure",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_data_after_video() {
+        for video_path in fs::read_dir(VIDEO_PATH).unwrap() {
+            let path = video_path.unwrap().path();
+            let mut buffer = fs::read(&path).unwrap();
+            let length = buffer.len();
+            let file_type = infer::get(&buffer).unwrap();
+            buffer.extend_from_slice(b"%PDF-1.7");
+            assert_eq!(
+                validate_structure(&buffer, file_type.mime_type()),
+                Err(StructureError::TrailingData {
+                    offset: length,
+                    length: 8
+                }),
+                "{:?} with appended data schould not pass",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_data() {
+        for (name, mime_type) in [
+            ("file_example_PNG_500kB.png", "image/png"),
+            ("file_example_JPG_100kB.jpg", "image/jpeg"),
+            ("file_example_GIF_500kB.gif", "image/gif"),
+            ("file_example_WEBP_50kB.webp", "image/webp"),
+            ("file_example_favicon.ico", "image/vnd.microsoft.icon"),
+        ] {
+            let mut buffer = read_image(name);
+            let length = buffer.len();
+            buffer.extend_from_slice(b"PK\x03\x04<html>");
+            assert_eq!(
+                validate_structure(&buffer, mime_type),
+                Err(StructureError::TrailingData {
+                    offset: length,
+                    length: 10
+                }),
+                "{} with appended data schould not pass",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn archives_and_documents() {
+        for (path, mime_type) in [
+            ("res/other_extension/zip_2MB.zip", "application/zip"),
+            (
+                "res/other_extension/file-sample_100kB.docx",
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            ),
+        ] {
+            let mut buffer = fs::read(path).unwrap();
+            assert_eq!(validate_structure(&buffer, mime_type), Ok(()), "{}", path);
+            let length = buffer.len();
+            buffer.extend_from_slice(b"<?php");
+            assert_eq!(
+                validate_structure(&buffer, mime_type),
+                Err(StructureError::TrailingData {
+                    offset: length,
+                    length: 5
+                }),
+                "{} with appended data schould not pass",
+                path
+            );
+        }
+        let pdf = b"%PDF-1.7\n1 0 obj\n<<>>\nendobj\n%%EOF\r\n";
+        assert_eq!(container_end(pdf, "application/pdf"), Ok(Some(pdf.len())));
+        assert_eq!(
+            validate_structure(b"%PDF-1.7\n%%EOF\n<script>", "application/pdf"),
+            Err(StructureError::TrailingData {
+                offset: 15,
+                length: 8
+            })
+        );
+        assert_eq!(
+            validate_structure(b"%PDF-1.7\n", "application/pdf"),
+            Err(StructureError::MissingChunk("%%EOF"))
+        );
+    }
+
+    #[test]
+    fn truncated_files() {
+        for (name, mime_type) in [
+            ("file_example_PNG_500kB.png", "image/png"),
+            ("file_example_JPG_100kB.jpg", "image/jpeg"),
+            ("file_example_GIF_500kB.gif", "image/gif"),
+            ("file_example_favicon.ico", "image/vnd.microsoft.icon"),
+        ] {
+            let buffer = read_image(name);
+            assert_eq!(
+                validate_structure(&buffer[..buffer.len() - 100], mime_type),
+                Err(StructureError::Truncated),
+                "truncated {} schould not pass",
+                name
+            );
+        }
+        let buffer = read_image("file_example_WEBP_50kB.webp");
+        assert!(matches!(
+            validate_structure(&buffer[..buffer.len() - 100], "image/webp"),
+            Err(StructureError::InvalidRiffSize { .. })
+        ));
+    }
+
+    #[test]
+    fn corrupted_png_chunk() {
+        let mut buffer = read_image("file_example_PNG_500kB.png");
+        // first byte of the IHDR data
+        buffer[16] ^= 0xFF;
+        assert_eq!(
+            validate_structure(&buffer, "image/png"),
+            Err(StructureError::InvalidChunkCrc {
+                chunk: "IHDR".to_string(),
+                offset: 8
+            })
+        );
+    }
+
+    #[test]
+    fn corrupted_signature() {
+        let buffer = read_image("file_example_GIF_500kB.gif");
+        assert_eq!(
+            validate_structure(&buffer, "image/png"),
+            Err(StructureError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn tiff_structure() {
+        let mut buffer = read_image("file_example_TIFF_1MB.tif");
+        let length = buffer.len();
+        assert_eq!(validate_structure(&buffer, "image/tiff"), Ok(()));
+        assert_eq!(
+            validate_structure(&buffer[..length - 100], "image/tiff"),
+            Err(StructureError::Truncated)
+        );
+        buffer.extend_from_slice(b"<script>");
+        assert_eq!(
+            validate_structure(&buffer, "image/tiff"),
+            Err(StructureError::TrailingData {
+                offset: length,
+                length: 8
+            })
+        );
+        // an IFD whose next IFD is itself
+        let looping = b"II*\0\x08\0\0\0\0\0\x08\0\0\0";
+        assert_eq!(
+            validate_structure(looping, "image/tiff"),
+            Err(StructureError::InvalidSegment { offset: 8 })
+        );
+    }
+
+    #[test]
+    fn unsupported_format() {
+        assert_eq!(container_end(b"BM", "image/bmp"), Ok(None));
+        assert_eq!(validate_structure(b"BM", "image/bmp"), Ok(()));
+    }
+}
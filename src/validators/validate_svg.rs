@@ -0,0 +1,526 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Verification and sanitisation of SVG images : scripts, event handlers, javascript: URLs, foreign objects and external references are removed.
+
+use crate::DEFAULT_MAX_SIZE;
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, Writer};
+use std::fmt;
+use std::fs::File;
+use std::io::{Error, Read};
+use std::path::Path;
+
+// embedded raster images that can be referenced by a data URL. data:image/svg+xml is refused, it could contain a script.
+const ALLOWED_DATA_URLS: [&str; 4] = [
+    "data:image/png",
+    "data:image/jpeg",
+    "data:image/gif",
+    "data:image/webp",
+];
+
+// Kind of dangerous content found in an SVG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvgThreat {
+    // <script> or <handler> element
+    Script,
+    // on* attribute (onload, onclick, ...), or an animation of such an attribute
+    EventHandler,
+    // javascript: or vbscript: URL
+    JavascriptUrl,
+    // <foreignObject> element, or HTML embedding elements (<iframe>, <embed>, <object>)
+    ForeignObject,
+    // reference to a resource outside the document (href, url(), @import, xml-stylesheet)
+    ExternalReference,
+}
+
+impl fmt::Display for SvgThreat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SvgThreat::Script => "script",
+            SvgThreat::EventHandler => "event handler",
+            SvgThreat::JavascriptUrl => "javascript URL",
+            SvgThreat::ForeignObject => "foreign object",
+            SvgThreat::ExternalReference => "external reference",
+        })
+    }
+}
+
+// Something removed from an SVG : a whole element if attribute is None, only the attribute otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgRemoval {
+    pub threat: SvgThreat,
+    pub element: String,
+    pub attribute: Option<String>,
+}
+
+// Reasons why an SVG is refused by the validator.
+#[derive(Debug)]
+pub enum SvgValidationError {
+    // the file doesn't exist or is not readable
+    Unreadable(Error),
+    // the file is bigger than the maximum size (in bytes)
+    TooLarge { size: u64, max: u64 },
+    // the content is not valid UTF-8 from this offset
+    InvalidUtf8 { offset: usize },
+    // the XML is not well-formed
+    Xml { offset: u64, message: String },
+    // a DOCTYPE has been found. It is always refused, so no entity can be declared and expanded (XXE, billion laughs)
+    DocumentType,
+    // the root element is not <svg>
+    NotSvg,
+    // dangerous content has been found and the validator doesn't strip it
+    Unsafe(Vec<SvgRemoval>),
+}
+
+impl fmt::Display for SvgValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgValidationError::Unreadable(e) => write!(f, "file is not readable: {}", e),
+            SvgValidationError::TooLarge { size, max } => write!(
+                f,
+                "file size of {} bytes exceeds the maximum of {} bytes",
+                size, max
+            ),
+            SvgValidationError::InvalidUtf8 { offset } => {
+                write!(f, "invalid UTF-8 at offset {}", offset)
+            }
+            SvgValidationError::Xml { offset, message } => {
+                write!(f, "invalid XML at offset {}: {}", offset, message)
+            }
+            SvgValidationError::DocumentType => write!(f, "DOCTYPE is not allowed"),
+            SvgValidationError::NotSvg => write!(f, "root element is not svg"),
+            SvgValidationError::Unsafe(removals) => {
+                write!(f, "dangerous content found :")?;
+                for removal in removals {
+                    write!(f, " {} in <{}>,", removal.threat, removal.element)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SvgValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SvgValidationError::Unreadable(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for SvgValidationError {
+    fn from(e: Error) -> Self {
+        SvgValidationError::Unreadable(e)
+    }
+}
+
+// An accepted SVG, without its dangerous content, and the list of what has been removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizedSvg {
+    pub content: String,
+    pub removed: Vec<SvgRemoval>,
+}
+
+// Policy used to accept or sanitise an SVG. Build it with SvgValidator::new() and the builder methods.
+// By default, the dangerous content is removed and reported, and the size is limited to DEFAULT_MAX_SIZE.
+#[derive(Debug, Clone)]
+pub struct SvgValidator {
+    max_size: Option<u64>,
+    strip: bool,
+}
+
+impl Default for SvgValidator {
+    fn default() -> Self {
+        SvgValidator {
+            max_size: Some(DEFAULT_MAX_SIZE),
+            strip: true,
+        }
+    }
+}
+
+impl SvgValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Maximum size of the file in bytes.
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    // if true, the dangerous content is removed and reported in the SanitizedSvg.
+    // if false, an SVG with dangerous content is refused with SvgValidationError::Unsafe.
+    pub fn strip(mut self, strip: bool) -> Self {
+        self.strip = strip;
+        self
+    }
+
+    // Verify the SVG at the given path with this policy.
+    // The size is checked from the metadata, then the file is read with a bounded reader.
+    pub fn validate<P: AsRef<Path>>(&self, path: P) -> Result<SanitizedSvg, SvgValidationError> {
+        let file = File::open(path)?;
+        self.check_size(file.metadata()?.len())?;
+        let mut buffer = Vec::new();
+        match self.max_size {
+            // we read one byte more than the maximum to know if the content is too large
+            Some(max) => file.take(max.saturating_add(1)).read_to_end(&mut buffer)?,
+            None => (&file).read_to_end(&mut buffer)?,
+        };
+        self.validate_bytes(&buffer)
+    }
+
+    // Verify an in-memory SVG with this policy. The XML is parsed as a stream, nothing is expanded.
+    pub fn validate_bytes(&self, buffer: &[u8]) -> Result<SanitizedSvg, SvgValidationError> {
+        self.check_size(buffer.len() as u64)?;
+        let text = std::str::from_utf8(buffer).map_err(|e| SvgValidationError::InvalidUtf8 {
+            offset: e.valid_up_to(),
+        })?;
+        let mut reader = Reader::from_str(text);
+        let mut writer = Writer::new(Vec::new());
+        let mut removed = Vec::new();
+        let mut has_root = false;
+        // depth inside a removed element, 0 if no element is being removed
+        let mut skipped = 0usize;
+        // events of a <style> element, kept until its content is verified
+        let mut style: Option<(Vec<Event>, String)> = None;
+        loop {
+            let xml_error =
+                |reader: &Reader<&[u8]>, e: &dyn fmt::Display| SvgValidationError::Xml {
+                    offset: reader.error_position(),
+                    message: e.to_string(),
+                };
+            let event = reader.read_event().map_err(|e| xml_error(&reader, &e))?;
+            if skipped > 0 {
+                match event {
+                    Event::Start(_) => skipped += 1,
+                    Event::End(_) => skipped -= 1,
+                    _ => {}
+                }
+                continue;
+            }
+            let event = match event {
+                Event::DocType(_) => return Err(SvgValidationError::DocumentType),
+                Event::Eof => break,
+                // <?xml-stylesheet ...?> loads a stylesheet
+                Event::PI(pi) => {
+                    removed.push(SvgRemoval {
+                        threat: SvgThreat::ExternalReference,
+                        element: format!("?{}", String::from_utf8_lossy(pi.target())),
+                        attribute: None,
+                    });
+                    continue;
+                }
+                Event::Start(ref element) | Event::Empty(ref element) => {
+                    if !has_root && !element.local_name().as_ref().eq_ignore_ascii_case(b"svg") {
+                        return Err(SvgValidationError::NotSvg);
+                    }
+                    has_root = true;
+                    let is_start = matches!(event, Event::Start(_));
+                    match sanitize_element(element, &mut removed)
+                        .map_err(|e| xml_error(&reader, &e))?
+                    {
+                        Some(sanitized) if is_start => Event::Start(sanitized),
+                        Some(sanitized) => Event::Empty(sanitized),
+                        None => {
+                            skipped = if is_start { 1 } else { 0 };
+                            continue;
+                        }
+                    }
+                }
+                event => event.into_owned(),
+            };
+            // the content of a <style> is verified once the element is complete
+            if let Event::Start(element) = &event {
+                if element.local_name().as_ref().eq_ignore_ascii_case(b"style") {
+                    style = Some((vec![event], String::new()));
+                    continue;
+                }
+            }
+            if let Some((events, css)) = style.as_mut() {
+                match &event {
+                    Event::Text(text) => {
+                        css.push_str(&text.unescape().map_err(|e| xml_error(&reader, &e))?)
+                    }
+                    Event::CData(text) => css.push_str(&String::from_utf8_lossy(text)),
+                    _ => {}
+                }
+                let end = matches!(event, Event::End(_));
+                events.push(event);
+                if end {
+                    let (events, css) = style.take().unwrap_or_default();
+                    if is_unsafe_css(&normalize(&css)) {
+                        removed.push(SvgRemoval {
+                            threat: SvgThreat::ExternalReference,
+                            element: "style".to_string(),
+                            attribute: None,
+                        });
+                    } else {
+                        for event in events {
+                            writer.write_event(event)?;
+                        }
+                    }
+                }
+                continue;
+            }
+            writer.write_event(event)?;
+        }
+        if !has_root {
+            return Err(SvgValidationError::NotSvg);
+        }
+        if !self.strip && !removed.is_empty() {
+            return Err(SvgValidationError::Unsafe(removed));
+        }
+        Ok(SanitizedSvg {
+            content: String::from_utf8_lossy(&writer.into_inner()).into_owned(),
+            removed,
+        })
+    }
+
+    fn check_size(&self, size: u64) -> Result<(), SvgValidationError> {
+        match self.max_size {
+            Some(max) if size > max => Err(SvgValidationError::TooLarge { size, max }),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Return the element without its dangerous attributes, or None if the whole element must be removed.
+fn sanitize_element(
+    element: &BytesStart,
+    removed: &mut Vec<SvgRemoval>,
+) -> Result<Option<BytesStart<'static>>, quick_xml::Error> {
+    let qualified_name = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+    let local_name = String::from_utf8_lossy(element.local_name().as_ref()).to_lowercase();
+    let element_threat = match local_name.as_str() {
+        "script" | "handler" => Some(SvgThreat::Script),
+        "foreignobject" | "iframe" | "embed" | "object" => Some(SvgThreat::ForeignObject),
+        _ => None,
+    };
+    if let Some(threat) = element_threat {
+        removed.push(SvgRemoval {
+            threat,
+            element: qualified_name,
+            attribute: None,
+        });
+        return Ok(None);
+    }
+    let mut sanitized = BytesStart::new(qualified_name.clone());
+    for attribute in element.attributes() {
+        let attribute = attribute?;
+        match attribute_threat(&local_name, &attribute)? {
+            Some(threat) => removed.push(SvgRemoval {
+                threat,
+                element: qualified_name.clone(),
+                attribute: Some(String::from_utf8_lossy(attribute.key.as_ref()).into_owned()),
+            }),
+            None => sanitized.push_attribute(attribute),
+        }
+    }
+    Ok(Some(sanitized))
+}
+
+// the value is compared once unescaped and normalised, so "&#106;ava&#x09;script:" is found too
+fn attribute_threat(
+    element: &str,
+    attribute: &Attribute,
+) -> Result<Option<SvgThreat>, quick_xml::Error> {
+    let name = String::from_utf8_lossy(attribute.key.local_name().as_ref()).to_lowercase();
+    let value = normalize(&attribute.unescape_value()?);
+    let threat = if name.starts_with("on") {
+        Some(SvgThreat::EventHandler)
+    } else if value.contains("javascript:") || value.contains("vbscript:") {
+        Some(SvgThreat::JavascriptUrl)
+    } else if name == "attributename" && value.starts_with("on") {
+        // <set attributeName="onclick" to="..."> would add an event handler
+        Some(SvgThreat::EventHandler)
+    } else if ((name == "href" || name == "src") && !is_safe_reference(element, &value))
+        || (name == "style" && is_unsafe_css(&value))
+        || has_external_url(&value)
+    {
+        Some(SvgThreat::ExternalReference)
+    } else {
+        None
+    };
+    Ok(threat)
+}
+
+// the browsers ignore the whitespaces and control characters in a URL scheme, "java\tscript:" is a javascript URL
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// an internal fragment (#id), an embedded raster image, or a link of an <a> element
+fn is_safe_reference(element: &str, reference: &str) -> bool {
+    reference.is_empty()
+        || reference.starts_with('#')
+        || ALLOWED_DATA_URLS
+            .iter()
+            .any(|prefix| reference.starts_with(prefix))
+        || (element == "a"
+            && ["http://", "https://", "mailto:"]
+                .iter()
+                .any(|scheme| reference.starts_with(scheme)))
+}
+
+// url(...) that is not an internal fragment or an embedded raster image
+fn has_external_url(value: &str) -> bool {
+    value.match_indices("url(").any(|(pos, _)| {
+        let target = value[pos + 4..].trim_start_matches(['"', '\'']);
+        !target.starts_with('#')
+            && !ALLOWED_DATA_URLS
+                .iter()
+                .any(|prefix| target.starts_with(prefix))
+    })
+}
+
+// CSS escapes (\75rl) could hide a url(), they are refused with @import and external URLs
+fn is_unsafe_css(css: &str) -> bool {
+    css.contains("@import") || css.contains('\\') || has_external_url(css)
+}
+
+// Verify and sanitise the SVG at the given path with the default policy.
+pub fn validate_svg_file(path: &str) -> Result<SanitizedSvg, SvgValidationError> {
+    SvgValidator::new().validate(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{validate_svg_file, SvgThreat, SvgValidationError, SvgValidator, DEFAULT_MAX_SIZE};
+
+    const CLEAN: &str = "res/svg/clean.svg";
+    const MALICIOUS: &str = "res/svg/malicious.svg";
+
+    #[test]
+    fn clean_svg() {
+        let sanitized = validate_svg_file(CLEAN).unwrap();
+        assert!(sanitized.removed.is_empty(), "{:?}", sanitized.removed);
+        assert_eq!(
+            sanitized.content,
+            std::fs::read_to_string(CLEAN).unwrap(),
+            "A clean SVG schould not be modified"
+        );
+        assert!(SvgValidator::new().strip(false).validate(CLEAN).is_ok());
+    }
+
+    #[test]
+    fn malicious_svg() {
+        let sanitized = validate_svg_file(MALICIOUS).unwrap();
+        let threats: Vec<SvgThreat> = sanitized.removed.iter().map(|r| r.threat).collect();
+        assert_eq!(
+            threats,
+            vec![
+                SvgThreat::ExternalReference, // xml-stylesheet
+                SvgThreat::EventHandler,      // svg onload
+                SvgThreat::Script,
+                SvgThreat::EventHandler,  // circle onmouseover
+                SvgThreat::JavascriptUrl, // a xlink:href
+                SvgThreat::ForeignObject,
+                SvgThreat::ExternalReference, // image href
+                SvgThreat::ExternalReference, // rect style
+                SvgThreat::ExternalReference, // style @import
+                SvgThreat::EventHandler,      // set attributeName
+            ]
+        );
+        let content = sanitized.content.to_lowercase();
+        for forbidden in [
+            "<script",
+            "onload",
+            "onmouseover",
+            "script:",
+            "foreignobject",
+            "iframe",
+            "evil.example",
+            "@import",
+            "onclick",
+        ] {
+            assert!(
+                !content.contains(forbidden),
+                "{} schould have been removed",
+                forbidden
+            );
+        }
+        assert!(content.contains("<circle"), "Safe elements schould be kept");
+        assert!(matches!(
+            SvgValidator::new().strip(false).validate(MALICIOUS),
+            Err(SvgValidationError::Unsafe(removals)) if removals.len() == 10
+        ));
+    }
+
+    #[test]
+    fn entities_refused() {
+        let xxe = b"<?xml version=\"1.0\"?><!DOCTYPE svg [<!ENTITY xxe SYSTEM \"file:///etc/passwd\">]><svg>&xxe;</svg>";
+        assert!(matches!(
+            SvgValidator::new().validate_bytes(xxe),
+            Err(SvgValidationError::DocumentType)
+        ));
+        assert!(
+            matches!(
+                SvgValidator::new().validate_bytes(b"<svg><text x=\"&xxe;\"/></svg>"),
+                Err(SvgValidationError::Xml { .. })
+            ),
+            "An undeclared entity schould not be accepted"
+        );
+    }
+
+    #[test]
+    fn invalid_svg() {
+        for (content, error) in [
+            (&b"<html><script/></html>"[..], "NotSvg"),
+            (b"", "NotSvg"),
+            (b"<svg><g></svg>", "Xml"),
+            (b"<svg a=\"1\" a=\"2\"/>", "Xml"),
+            (b"<svg>\xFF</svg>", "InvalidUtf8"),
+        ] {
+            let result = SvgValidator::new().validate_bytes(content);
+            assert!(
+                format!("{:?}", result).starts_with(&format!("Err({}", error)),
+                "{:?} schould be refused with {}, got {:?}",
+                String::from_utf8_lossy(content),
+                error,
+                result
+            );
+        }
+        assert!(matches!(
+            SvgValidator::new().max_size(100).validate(CLEAN),
+            Err(SvgValidationError::TooLarge { max: 100, .. })
+        ));
+        let path = std::env::temp_dir().join(format!("validate_svg_{}.svg", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        // a sparse file, nothing is written on the disk
+        file.set_len(DEFAULT_MAX_SIZE + 1).unwrap();
+        let result = SvgValidator::new().validate(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(
+                result,
+                Err(SvgValidationError::TooLarge {
+                    max: DEFAULT_MAX_SIZE,
+                    ..
+                })
+            ),
+            "The size schould be limited by default"
+        );
+        assert!(
+            validate_svg_file("res/image/file_example_PNG_500kB.png").is_err(),
+            "A PNG is not an SVG"
+        );
+    }
+
+    #[test]
+    fn safe_references() {
+        let svg = b"<svg xmlns:xlink=\"http://www.w3.org/1999/xlink\"><image xlink:href=\"data:image/png;base64,AAAA\"/><image href=\"data:image/svg+xml;base64,AAAA\"/><rect fill=\"url(#p)\" filter=\"url(https://evil/f)\"/></svg>";
+        let sanitized = SvgValidator::new().validate_bytes(svg).unwrap();
+        assert_eq!(sanitized.removed.len(), 2);
+        assert!(sanitized.content.contains("data:image/png"));
+        assert!(sanitized.content.contains("url(#p)"));
+        assert!(!sanitized.content.contains("svg+xml"));
+        assert!(!sanitized.content.contains("evil"));
+    }
+}
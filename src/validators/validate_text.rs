@@ -0,0 +1,441 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Verification of the text files (plain text, CSV and JSON), that infer can't identify with a magic number.
+
+use crate::{csv_report, file_extension, json_depth, DEFAULT_MAX_SIZE};
+use std::fmt;
+use std::fs::File;
+use std::io::{Error, Read};
+use std::path::Path;
+
+// UTF-8 byte order mark, accepted at the start of a text
+const UTF8_BOM: &str = "\u{FEFF}";
+
+// Reasons why a text file is refused by the validator. The offsets are in bytes, the rows and columns start at 0.
+#[derive(Debug)]
+pub enum TextValidationError {
+    // the file doesn't exist or is not readable
+    Unreadable(Error),
+    // the file is bigger than the maximum size (in bytes)
+    TooLarge {
+        size: u64,
+        max: u64,
+    },
+    // the content is not valid UTF-8 from this offset
+    InvalidUtf8 {
+        offset: usize,
+    },
+    // a control character (other than tab, line feed and carriage return) has been found at this offset
+    ControlCharacter {
+        offset: usize,
+    },
+    // a quote is not closed or is misplaced in this row of the CSV
+    CsvSyntax {
+        row: usize,
+    },
+    // this row of the CSV doesn't have the same number of columns as the first row
+    InconsistentColumns {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    // the CSV has more rows than the maximum
+    TooManyRows {
+        max: usize,
+    },
+    // this row of the CSV has more columns than the maximum
+    TooManyColumns {
+        row: usize,
+        max: usize,
+    },
+    // this cell of the CSV starts with a formula character and formulas are refused
+    FormulaInjection {
+        row: usize,
+        column: usize,
+    },
+    // the JSON is not valid at this offset
+    JsonSyntax {
+        offset: usize,
+    },
+    // the nesting of the JSON exceeds the maximum depth at this offset
+    JsonTooDeep {
+        offset: usize,
+        max: usize,
+    },
+}
+
+impl fmt::Display for TextValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextValidationError::Unreadable(e) => write!(f, "file is not readable: {}", e),
+            TextValidationError::TooLarge { size, max } => write!(
+                f,
+                "file size of {} bytes exceeds the maximum of {} bytes",
+                size, max
+            ),
+            TextValidationError::InvalidUtf8 { offset } => {
+                write!(f, "invalid UTF-8 at offset {}", offset)
+            }
+            TextValidationError::ControlCharacter { offset } => {
+                write!(f, "control character at offset {}", offset)
+            }
+            TextValidationError::CsvSyntax { row } => {
+                write!(f, "misplaced or unterminated quote in row {}", row)
+            }
+            TextValidationError::InconsistentColumns {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} columns instead of {}",
+                row, found, expected
+            ),
+            TextValidationError::TooManyRows { max } => {
+                write!(f, "CSV exceeds the maximum of {} rows", max)
+            }
+            TextValidationError::TooManyColumns { row, max } => {
+                write!(f, "row {} exceeds the maximum of {} columns", row, max)
+            }
+            TextValidationError::FormulaInjection { row, column } => write!(
+                f,
+                "cell at row {} and column {} starts with a formula character",
+                row, column
+            ),
+            TextValidationError::JsonSyntax { offset } => {
+                write!(f, "invalid JSON at offset {}", offset)
+            }
+            TextValidationError::JsonTooDeep { offset, max } => write!(
+                f,
+                "JSON nesting exceeds the maximum depth of {} at offset {}",
+                max, offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TextValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TextValidationError::Unreadable(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for TextValidationError {
+    fn from(e: Error) -> Self {
+        TextValidationError::Unreadable(e)
+    }
+}
+
+// Format of a text file, chosen from the extension of its filename.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    PlainText,
+    Csv,
+    Json,
+}
+
+impl TextFormat {
+    // "csv" for a CSV, "json" for a JSON, plain text for any other extension
+    pub fn from_filename(filename: &str) -> Self {
+        match file_extension(filename).as_deref() {
+            Some("csv") => TextFormat::Csv,
+            Some("json") => TextFormat::Json,
+            _ => TextFormat::PlainText,
+        }
+    }
+}
+
+// Position of a cell in a CSV, starting at 0. The first row is the header, if the CSV has one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellPosition {
+    pub row: usize,
+    pub column: usize,
+}
+
+// What has been found in an accepted CSV.
+// formula_cells : the cells starting with =, +, -, @, tab or carriage return, that a spreadsheet could run as a formula.
+//                 Numbers like -12.5 are not reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvReport {
+    pub rows: usize,
+    pub columns: usize,
+    pub formula_cells: Vec<CellPosition>,
+}
+
+// What has been found in an accepted text file.
+// lines : number of lines of the text
+// csv : the report of a CSV, None for other formats
+// json_depth : the maximum nesting depth of a JSON (0 for a scalar), None for other formats
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextInfo {
+    pub format: TextFormat,
+    pub lines: usize,
+    pub csv: Option<CsvReport>,
+    pub json_depth: Option<usize>,
+}
+
+// Policy used to accept or refuse a text file. Build it with TextValidator::new() and the builder methods.
+// By default, the text must be UTF-8 without control characters, the size is limited to DEFAULT_MAX_SIZE,
+// there is no row or column limit, the CSV delimiter is ',', formula cells are reported but not refused and the JSON depth is limited to 64.
+#[derive(Debug, Clone)]
+pub struct TextValidator {
+    max_size: Option<u64>,
+    delimiter: char,
+    max_rows: Option<usize>,
+    max_columns: Option<usize>,
+    reject_formulas: bool,
+    max_depth: usize,
+}
+
+impl Default for TextValidator {
+    fn default() -> Self {
+        TextValidator {
+            max_size: Some(DEFAULT_MAX_SIZE),
+            delimiter: ',',
+            max_rows: None,
+            max_columns: None,
+            reject_formulas: false,
+            max_depth: 64,
+        }
+    }
+}
+
+impl TextValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Maximum size of the file in bytes.
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    // Delimiter of the cells of a CSV, ',' by default (';' is common too).
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    // Maximum number of rows of a CSV, header included.
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    // Maximum number of columns of a CSV.
+    pub fn max_columns(mut self, max_columns: usize) -> Self {
+        self.max_columns = Some(max_columns);
+        self
+    }
+
+    // if true, a CSV with a formula cell is refused instead of being reported in the CsvReport
+    pub fn reject_formulas(mut self, reject_formulas: bool) -> Self {
+        self.reject_formulas = reject_formulas;
+        self
+    }
+
+    // Maximum nesting depth of the objects and arrays of a JSON.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    // Verify the text file at the given path, with the format given by its extension.
+    // The size is checked from the metadata, then the file is read with a bounded reader.
+    pub fn validate<P: AsRef<Path>>(&self, path: P) -> Result<TextInfo, TextValidationError> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        self.check_size(file.metadata()?.len())?;
+        let mut buffer = Vec::new();
+        match self.max_size {
+            // we read one byte more than the maximum to know if the content is too large
+            Some(max) => file.take(max.saturating_add(1)).read_to_end(&mut buffer)?,
+            None => (&file).read_to_end(&mut buffer)?,
+        };
+        self.validate_bytes(&buffer, TextFormat::from_filename(&path.to_string_lossy()))
+    }
+
+    // Verify an in-memory content with the given format.
+    pub fn validate_bytes(
+        &self,
+        buffer: &[u8],
+        format: TextFormat,
+    ) -> Result<TextInfo, TextValidationError> {
+        self.check_size(buffer.len() as u64)?;
+        let text = validate_utf8_text(buffer)?;
+        let mut info = TextInfo {
+            format,
+            lines: text.lines().count(),
+            csv: None,
+            json_depth: None,
+        };
+        match format {
+            TextFormat::PlainText => {}
+            TextFormat::Csv => {
+                let report = csv_report(text, self.delimiter, self.max_rows, self.max_columns)?;
+                if self.reject_formulas {
+                    if let Some(cell) = report.formula_cells.first() {
+                        return Err(TextValidationError::FormulaInjection {
+                            row: cell.row,
+                            column: cell.column,
+                        });
+                    }
+                }
+                info.csv = Some(report);
+            }
+            TextFormat::Json => info.json_depth = Some(json_depth(text, self.max_depth)?),
+        }
+        Ok(info)
+    }
+
+    fn check_size(&self, size: u64) -> Result<(), TextValidationError> {
+        match self.max_size {
+            Some(max) if size > max => Err(TextValidationError::TooLarge { size, max }),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Verify that the content is UTF-8 text without control characters (tab, line feed and carriage return are allowed).
+// Return the text, without its byte order mark.
+pub fn validate_utf8_text(buffer: &[u8]) -> Result<&str, TextValidationError> {
+    let text = std::str::from_utf8(buffer).map_err(|e| TextValidationError::InvalidUtf8 {
+        offset: e.valid_up_to(),
+    })?;
+    let bom = if text.starts_with(UTF8_BOM) {
+        UTF8_BOM.len()
+    } else {
+        0
+    };
+    let text = &text[bom..];
+    if let Some((offset, _)) = text
+        .char_indices()
+        .find(|(_, c)| c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+    {
+        return Err(TextValidationError::ControlCharacter {
+            offset: bom + offset,
+        });
+    }
+    Ok(text)
+}
+
+// Verify the text file at the given path with the default policy, with the format given by its extension.
+pub fn validate_text_file(path: &str) -> Result<TextInfo, TextValidationError> {
+    TextValidator::new().validate(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        validate_text_file, validate_utf8_text, TextFormat, TextValidationError, TextValidator,
+        DEFAULT_MAX_SIZE,
+    };
+
+    const CSV: &str = "res/other_extension/file_example_CSV_5000.csv";
+
+    #[test]
+    fn csv_fixture() {
+        let info = validate_text_file(CSV).unwrap();
+        assert_eq!(info.format, TextFormat::Csv);
+        assert_eq!(info.lines, 5001);
+        let report = info.csv.unwrap();
+        assert_eq!((report.rows, report.columns), (5001, 8));
+        assert!(report.formula_cells.is_empty());
+    }
+
+    #[test]
+    fn utf8_text() {
+        assert_eq!(
+            validate_utf8_text(b"hello\r\n\tworld").unwrap(),
+            "hello\r\n\tworld"
+        );
+        assert_eq!(
+            validate_utf8_text("\u{FEFF}caf\u{E9}".as_bytes()).unwrap(),
+            "caf\u{E9}",
+            "The byte order mark schould be removed"
+        );
+        assert!(matches!(
+            validate_utf8_text(b"caf\xE9"),
+            Err(TextValidationError::InvalidUtf8 { offset: 3 })
+        ));
+        assert!(matches!(
+            validate_utf8_text(b"ls\x1B[2J"),
+            Err(TextValidationError::ControlCharacter { offset: 2 })
+        ));
+        assert!(matches!(
+            validate_utf8_text(b"a\0b"),
+            Err(TextValidationError::ControlCharacter { offset: 1 })
+        ));
+    }
+
+    #[test]
+    fn binary_files_refused() {
+        for path in [
+            "res/image/file_example_PNG_500kB.png",
+            "res/other_extension/file_example_MP3_700KB.mp3",
+        ] {
+            assert!(TextValidator::new().validate(path).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn size_limit() {
+        assert!(matches!(
+            TextValidator::new().max_size(1000).validate(CSV),
+            Err(TextValidationError::TooLarge {
+                size: 284042,
+                max: 1000
+            })
+        ));
+        assert!(matches!(
+            TextValidator::new().validate("res/missing.csv"),
+            Err(TextValidationError::Unreadable(_))
+        ));
+        let path = std::env::temp_dir().join(format!("validate_text_{}.csv", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        // a sparse file, nothing is written on the disk
+        file.set_len(DEFAULT_MAX_SIZE + 1).unwrap();
+        let result = TextValidator::new().validate(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(
+                result,
+                Err(TextValidationError::TooLarge {
+                    max: DEFAULT_MAX_SIZE,
+                    ..
+                })
+            ),
+            "The size schould be limited by default"
+        );
+    }
+
+    #[test]
+    fn format_from_filename() {
+        assert_eq!(TextFormat::from_filename("data.CSV"), TextFormat::Csv);
+        assert_eq!(TextFormat::from_filename("data.json"), TextFormat::Json);
+        assert_eq!(
+            TextFormat::from_filename("notes.txt"),
+            TextFormat::PlainText
+        );
+        assert_eq!(TextFormat::from_filename("README"), TextFormat::PlainText);
+    }
+
+    #[test]
+    fn formula_rejection() {
+        let csv = b"name,amount\nalice,-12.5\n\"=HYPERLINK(\"\"http://evil\"\")\",1\n";
+        let info = TextValidator::new()
+            .validate_bytes(csv, TextFormat::Csv)
+            .unwrap();
+        assert_eq!(info.csv.unwrap().formula_cells.len(), 1);
+        assert!(matches!(
+            TextValidator::new()
+                .reject_formulas(true)
+                .validate_bytes(csv, TextFormat::Csv),
+            Err(TextValidationError::FormulaInjection { row: 2, column: 0 })
+        ));
+    }
+}
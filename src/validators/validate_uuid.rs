@@ -4,7 +4,8 @@
 // Objective : Library that allow the semmentic verication of UUID and verify if the content of a file match an UUID.
 
 use regex::Regex;
-use std::io::Error;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read};
 use uuid::Uuid;
 
 // Allow to validate the format of a v5 UUID in hyphenated format
@@ -14,19 +15,55 @@ pub fn validate_uuid(uuid: &str) -> bool {
     regex_uuid.is_match(uuid)
 }
 
+// Maximum size of a file read by validate_file_with_uuid, in bytes (1 GiB).
+pub const MAX_UUID_FILE_SIZE: u64 = 1 << 30;
+
 // Allow to validate that a UUID in correctly linked to a file content.
-// No unitary test is done but this function as been verified manually.
+// The file is not read if it is bigger than MAX_UUID_FILE_SIZE, an error of kind InvalidData is returned.
 pub fn validate_file_with_uuid(uuid: &str, filename: &str) -> Result<bool, Error> {
-    let buffer = std::fs::read(filename)?;
-    Ok(Uuid::new_v5(&Uuid::default(), &buffer)
+    validate_file_with_uuid_max_size(uuid, filename, MAX_UUID_FILE_SIZE)
+}
+
+// Same as validate_file_with_uuid, with the given maximum size in bytes.
+// The size is checked from the metadata, then the file is read with a bounded reader, in case it grows in the meantime.
+pub fn validate_file_with_uuid_max_size(
+    uuid: &str,
+    filename: &str,
+    max_size: u64,
+) -> Result<bool, Error> {
+    let too_large = || Error::new(ErrorKind::InvalidData, "file exceeds the maximum size");
+    let file = File::open(filename)?;
+    if file.metadata()?.len() > max_size {
+        return Err(too_large());
+    }
+    let mut buffer = Vec::new();
+    file.take(max_size.saturating_add(1))
+        .read_to_end(&mut buffer)?;
+    if buffer.len() as u64 > max_size {
+        return Err(too_large());
+    }
+    Ok(validate_bytes_with_uuid(uuid, &buffer))
+}
+
+// Allow to validate that a UUID in correctly linked to an in-memory content.
+pub fn validate_bytes_with_uuid(uuid: &str, buffer: &[u8]) -> bool {
+    content_uuid(buffer) == uuid
+}
+
+// Return the UUID of a content, a v5 UUID in the default namespace (deterministic), in hyphenated format.
+pub fn content_uuid(buffer: &[u8]) -> String {
+    Uuid::new_v5(&Uuid::default(), buffer)
         .as_hyphenated()
         .to_string()
-        == uuid)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::validate_uuid;
+    use crate::{
+        content_uuid, validate_bytes_with_uuid, validate_file_with_uuid,
+        validate_file_with_uuid_max_size, validate_uuid,
+    };
+    use std::io::ErrorKind;
 
     #[test]
     fn valid_uuid() {
@@ -79,4 +116,32 @@ mod tests {
             "Only hex char are valid in UUID"
         );
     }
+
+    #[test]
+    fn uuid_of_buffer() {
+        assert!(
+            validate_bytes_with_uuid("e129f27c-5103-5c5c-844b-cdf0a15e160d", b""),
+            "UUID v5 of an empty content in the default namespace"
+        );
+        assert!(
+            !validate_bytes_with_uuid("00000000-0000-0000-0000-000000000000", b""),
+            "UUID not linked to the content schould not pass"
+        );
+    }
+
+    #[test]
+    fn uuid_of_file() {
+        let path = "res/image/file_example_PNG_500kB.png";
+        let uuid = content_uuid(&std::fs::read(path).unwrap());
+        assert!(validate_file_with_uuid(&uuid, path).unwrap());
+        assert!(!validate_file_with_uuid("00000000-0000-0000-0000-000000000000", path).unwrap());
+        assert!(validate_file_with_uuid_max_size(&uuid, path, 512596).unwrap());
+        assert_eq!(
+            validate_file_with_uuid_max_size(&uuid, path, 1000)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData,
+            "A file bigger than the maximum schould not be read"
+        );
+    }
 }
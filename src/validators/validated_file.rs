@@ -0,0 +1,124 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : The content of a file that has been read once and verified, so every later step uses exactly the verified bytes.
+
+use crate::{
+    content_uuid, image_metadata, strip_metadata, validate_bytes_with_uuid, ImageMetadata,
+    MediaInfo, MediaKind, StructureError,
+};
+use std::fs;
+use std::io::Error;
+use std::path::Path;
+
+// Content of a file accepted by a FileValidator, with its detected type and its UUID.
+// It can only be built by a validator, thus its content has always been verified.
+#[derive(Debug, Clone)]
+pub struct ValidatedFile {
+    content: Vec<u8>,
+    info: MediaInfo,
+    uuid: String,
+}
+
+impl ValidatedFile {
+    pub(crate) fn new(content: Vec<u8>, info: MediaInfo) -> Self {
+        let uuid = content_uuid(&content);
+        ValidatedFile {
+            content,
+            info,
+            uuid,
+        }
+    }
+
+    // the bytes that have been verified
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    // the detected type of the content
+    pub fn info(&self) -> &MediaInfo {
+        &self.info
+    }
+
+    // the UUID of the content, in hyphenated format (see content_uuid)
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    // true if the UUID given by a user is the UUID of this content
+    pub fn matches_uuid(&self, uuid: &str) -> bool {
+        validate_bytes_with_uuid(uuid, &self.content)
+    }
+
+    // the metadata (EXIF, XMP, ...) of an image, None for other kinds of content or unsupported formats
+    pub fn metadata(&self) -> Result<Option<ImageMetadata>, StructureError> {
+        if self.info.kind != MediaKind::Image {
+            return Ok(None);
+        }
+        image_metadata(&self.content, self.info.mime_type)
+    }
+
+    // A copy of this file without the metadata of the image, with the UUID of the stripped content.
+    // Other kinds of content and unsupported formats are returned unchanged.
+    pub fn strip_metadata(&self) -> Result<ValidatedFile, StructureError> {
+        if self.info.kind != MediaKind::Image {
+            return Ok(self.clone());
+        }
+        match strip_metadata(&self.content, self.info.mime_type)? {
+            Some(content) => Ok(ValidatedFile::new(content, self.info.clone())),
+            None => Ok(self.clone()),
+        }
+    }
+
+    // Write the verified content to the given path, e.g. in the storage directory.
+    pub fn persist<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        fs::write(path, &self.content)
+    }
+
+    pub fn into_content(self) -> Vec<u8> {
+        self.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{content_uuid, FileValidationError, FileValidator, MediaKind};
+    use std::fs;
+
+    #[test]
+    fn read_once() {
+        let path = "res/image/file_example_PNG_500kB.png";
+        let validated = FileValidator::new().validate_and_read(path).unwrap();
+        let content = fs::read(path).unwrap();
+        assert_eq!(validated.content(), content.as_slice());
+        assert_eq!(validated.info().kind, MediaKind::Image);
+        assert_eq!(validated.uuid(), content_uuid(&content));
+        assert!(validated.matches_uuid(&content_uuid(&content)));
+        assert!(!validated.matches_uuid("00000000-0000-0000-0000-000000000000"));
+    }
+
+    #[test]
+    fn invalid_file_not_read() {
+        assert!(matches!(
+            FileValidator::new().validate_and_read("res/other_extension/zip_2MB.zip"),
+            Err(FileValidationError::DisallowedCategory(_))
+        ));
+        assert!(matches!(
+            FileValidator::new()
+                .max_size(1000)
+                .validate_and_read("res/video/file_example_MP4_480_1_5MG.mp4"),
+            Err(FileValidationError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn persisted_content_is_verified_content() {
+        let validated = FileValidator::new()
+            .validate_and_read("res/video/file_example_WEBM_480_900KB.webm")
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("{}.webm", validated.uuid()));
+        validated.persist(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), validated.content());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(validated.clone().into_content(), validated.content());
+    }
+}
@@ -0,0 +1,593 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Read the metadata of a video (duration, dimensions and codecs of the tracks) from the headers of its container.
+
+use crate::{
+    read_ebml_header, read_u16_le, read_u32_be, read_u32_le, read_u64_be, read_u64_le, Dimensions,
+    StructureError,
+};
+use std::time::Duration;
+
+// GUID of the ASF objects used to read the metadata of a WMV file
+const ASF_HEADER: [u8; 16] = [
+    0x30, 0x26, 0xB2, 0x75, 0x8E, 0x66, 0xCF, 0x11, 0xA6, 0xD9, 0x00, 0xAA, 0x00, 0x62, 0xCE, 0x6C,
+];
+const ASF_FILE_PROPERTIES: [u8; 16] = [
+    0xA1, 0xDC, 0xAB, 0x8C, 0x47, 0xA9, 0xCF, 0x11, 0x8E, 0xE4, 0x00, 0xC0, 0x0C, 0x20, 0x53, 0x65,
+];
+const ASF_STREAM_PROPERTIES: [u8; 16] = [
+    0x91, 0x07, 0xDC, 0xB7, 0xB7, 0xA9, 0xCF, 0x11, 0x8E, 0xE6, 0x00, 0xC0, 0x0C, 0x20, 0x53, 0x65,
+];
+const ASF_VIDEO_MEDIA: [u8; 16] = [
+    0xC0, 0xEF, 0x19, 0xBC, 0x4D, 0x5B, 0xCF, 0x11, 0xA8, 0xFD, 0x00, 0x80, 0x5F, 0x5C, 0x44, 0x2B,
+];
+const ASF_AUDIO_MEDIA: [u8; 16] = [
+    0x40, 0x9E, 0x69, 0xF8, 0x4D, 0x5B, 0xCF, 0x11, 0xA8, 0xFD, 0x00, 0x80, 0x5F, 0x5C, 0x44, 0x2B,
+];
+
+// EBML IDs used to read the metadata of a WebM or Matroska file
+const EBML_SEGMENT: u32 = 0x1853_8067;
+const EBML_INFO: u32 = 0x1549_A966;
+const EBML_TIMECODE_SCALE: u32 = 0x2A_D7B1;
+const EBML_DURATION: u32 = 0x4489;
+const EBML_TRACKS: u32 = 0x1654_AE6B;
+const EBML_TRACK_ENTRY: u32 = 0xAE;
+const EBML_TRACK_TYPE: u32 = 0x83;
+const EBML_CODEC_ID: u32 = 0x86;
+const EBML_VIDEO: u32 = 0xE0;
+const EBML_PIXEL_WIDTH: u32 = 0xB0;
+const EBML_PIXEL_HEIGHT: u32 = 0xBA;
+
+// Kind of content of a track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Other,
+}
+
+// A track of a video and the codec used to encode it, as identified by the container :
+// a fourcc for MP4, MOV, AVI and WMV video tracks (e.g. "avc1", "H264", "WMV2"), a codec ID for WebM (e.g. "V_VP8"),
+// and the name of the WAVEFORMATEX tag for AVI and WMV audio tracks (e.g. "aac", "wmav2").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Track {
+    pub kind: TrackKind,
+    pub codec: String,
+}
+
+// Metadata read from the headers of a video container. A value that is not declared in the container is None.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoMetadata {
+    pub duration: Option<Duration>,
+    pub dimensions: Option<Dimensions>,
+    pub tracks: Vec<Track>,
+}
+
+// Read the metadata of the video, according to its MIME type.
+// Supported formats are MP4 and MOV (moov box), WebM and Matroska (EBML), AVI (RIFF hdrl list) and WMV (ASF header).
+// Return Ok(None) if the format is not supported.
+pub fn video_metadata(
+    buffer: &[u8],
+    mime_type: &str,
+) -> Result<Option<VideoMetadata>, StructureError> {
+    let metadata = match mime_type {
+        "video/mp4" | "video/x-m4v" | "video/quicktime" => iso_bmff_metadata(buffer)?,
+        "video/webm" | "video/x-matroska" => ebml_metadata(buffer)?,
+        "video/x-msvideo" => avi_metadata(buffer)?,
+        "video/x-ms-wmv" => asf_metadata(buffer)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(metadata))
+}
+
+// MP4, MOV : moov/mvhd gives the duration, each moov/trak gives its dimensions (tkhd), its kind (mdia/hdlr)
+// and its codec (first sample entry of mdia/minf/stbl/stsd)
+fn iso_bmff_metadata(buffer: &[u8]) -> Result<VideoMetadata, StructureError> {
+    let (moov, moov_end) =
+        find_box(buffer, 0, buffer.len(), b"moov").ok_or(StructureError::MissingChunk("moov"))?;
+    let mut metadata = VideoMetadata {
+        duration: None,
+        dimensions: None,
+        tracks: Vec::new(),
+    };
+    if let Some((mvhd, _)) = find_box(buffer, moov, moov_end, b"mvhd") {
+        let (timescale, duration) = match buffer.get(mvhd) {
+            Some(0) => (
+                read_u32_be(buffer, mvhd + 12),
+                read_u32_be(buffer, mvhd + 16)
+                    .filter(|d| *d != u32::MAX)
+                    .map(|d| d as u64),
+            ),
+            Some(1) => (
+                read_u32_be(buffer, mvhd + 20),
+                read_u64_be(buffer, mvhd + 24).filter(|d| *d != u64::MAX),
+            ),
+            _ => return Err(StructureError::InvalidSegment { offset: mvhd }),
+        };
+        // a 64 bits duration can be too long for a Duration in nanoseconds, it is then unknown
+        metadata.duration = match (timescale, duration) {
+            (Some(timescale), Some(duration)) if timescale > 0 => {
+                u64::try_from(duration as u128 * 1_000_000_000 / timescale as u128)
+                    .ok()
+                    .map(Duration::from_nanos)
+            }
+            _ => None,
+        };
+    }
+    let mut pos = moov;
+    while let Some((trak, trak_end)) = find_box(buffer, pos, moov_end, b"trak") {
+        pos = trak_end;
+        let handler = find_path(buffer, trak, trak_end, &[b"mdia", b"hdlr"])
+            .and_then(|(hdlr, _)| buffer.get(hdlr + 8..hdlr + 12));
+        let kind = match handler {
+            Some(b"vide") => TrackKind::Video,
+            Some(b"soun") => TrackKind::Audio,
+            _ => TrackKind::Other,
+        };
+        let codec = find_path(
+            buffer,
+            trak,
+            trak_end,
+            &[b"mdia", b"minf", b"stbl", b"stsd"],
+        )
+        .and_then(|(stsd, _)| buffer.get(stsd + 12..stsd + 16))
+        .map(fourcc)
+        .unwrap_or_default();
+        if kind == TrackKind::Video && metadata.dimensions.is_none() {
+            // width and height are the last fields of tkhd, in 16.16 fixed point
+            metadata.dimensions =
+                find_box(buffer, trak, trak_end, b"tkhd").and_then(|(_, tkhd_end)| {
+                    Some(Dimensions {
+                        width: read_u32_be(buffer, tkhd_end.checked_sub(8)?)? >> 16,
+                        height: read_u32_be(buffer, tkhd_end - 4)? >> 16,
+                    })
+                });
+        }
+        metadata.tracks.push(Track { kind, codec });
+    }
+    Ok(metadata)
+}
+
+// search the first box of the given type between start and end, return the offset of its content and its end
+fn find_box(buffer: &[u8], start: usize, end: usize, box_type: &[u8]) -> Option<(usize, usize)> {
+    let mut pos = start;
+    while pos + 8 <= end {
+        let (header, size) = match read_u32_be(buffer, pos)? {
+            0 => (8, (end - pos) as u64),
+            1 => (16, read_u64_be(buffer, pos + 8)?),
+            size => (8, size as u64),
+        };
+        if size < header || size > (end - pos) as u64 {
+            return None;
+        }
+        if buffer.get(pos + 4..pos + 8)? == box_type {
+            return Some((pos + header as usize, pos + size as usize));
+        }
+        pos += size as usize;
+    }
+    None
+}
+
+// search a box by its path of nested types
+fn find_path(buffer: &[u8], start: usize, end: usize, path: &[&[u8]]) -> Option<(usize, usize)> {
+    path.iter()
+        .try_fold((start, end), |(start, end), box_type| {
+            find_box(buffer, start, end, box_type)
+        })
+}
+
+// WebM, Matroska : Segment/Info gives the duration, Segment/Tracks/TrackEntry gives the kind, codec and dimensions of each track.
+// The reading stops at the first element of unknown size (usually the first Cluster) as the headers are before.
+fn ebml_metadata(buffer: &[u8]) -> Result<VideoMetadata, StructureError> {
+    let mut metadata = VideoMetadata {
+        duration: None,
+        dimensions: None,
+        tracks: Vec::new(),
+    };
+    let (segment, segment_end) = ebml_children(buffer, 0, buffer.len())
+        .find(|(id, _, _)| *id == EBML_SEGMENT)
+        .map(|(_, data, end)| (data, end))
+        .ok_or(StructureError::MissingChunk("Segment"))?;
+    for (id, data, end) in ebml_children(buffer, segment, segment_end) {
+        match id {
+            EBML_INFO => {
+                let mut timecode_scale = 1_000_000;
+                let mut duration = None;
+                for (id, data, end) in ebml_children(buffer, data, end) {
+                    match id {
+                        EBML_TIMECODE_SCALE => timecode_scale = read_ebml_uint(&buffer[data..end]),
+                        EBML_DURATION => duration = read_ebml_float(&buffer[data..end]),
+                        _ => {}
+                    }
+                }
+                // the duration is a float in timecode scale units (ns)
+                metadata.duration = duration
+                    .map(|d| (d * timecode_scale as f64).round())
+                    .filter(|ns| ns.is_finite() && *ns >= 0.0 && *ns < u64::MAX as f64)
+                    .map(|ns| Duration::from_nanos(ns as u64));
+            }
+            EBML_TRACKS => {
+                for (_, data, end) in
+                    ebml_children(buffer, data, end).filter(|(id, _, _)| *id == EBML_TRACK_ENTRY)
+                {
+                    let mut track = Track {
+                        kind: TrackKind::Other,
+                        codec: String::new(),
+                    };
+                    for (id, data, end) in ebml_children(buffer, data, end) {
+                        match id {
+                            EBML_TRACK_TYPE => {
+                                track.kind = match read_ebml_uint(&buffer[data..end]) {
+                                    1 => TrackKind::Video,
+                                    2 => TrackKind::Audio,
+                                    _ => TrackKind::Other,
+                                }
+                            }
+                            EBML_CODEC_ID => {
+                                track.codec = String::from_utf8_lossy(&buffer[data..end])
+                                    .trim_end_matches('\0')
+                                    .to_string()
+                            }
+                            EBML_VIDEO if metadata.dimensions.is_none() => {
+                                let (mut width, mut height) = (None, None);
+                                for (id, data, end) in ebml_children(buffer, data, end) {
+                                    match id {
+                                        EBML_PIXEL_WIDTH => {
+                                            width = Some(read_ebml_uint(&buffer[data..end]) as u32)
+                                        }
+                                        EBML_PIXEL_HEIGHT => {
+                                            height = Some(read_ebml_uint(&buffer[data..end]) as u32)
+                                        }
+                                        _ => {}
+                                    }
+                                }
+                                if let (Some(width), Some(height)) = (width, height) {
+                                    metadata.dimensions = Some(Dimensions { width, height });
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    metadata.tracks.push(track);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(metadata)
+}
+
+// iterate over the EBML elements of known size between start and end, as (ID, offset of the data, end of the element).
+// A Segment of unknown size extends to the end. The iteration stops at the first other element of unknown size.
+fn ebml_children(
+    buffer: &[u8],
+    start: usize,
+    end: usize,
+) -> impl Iterator<Item = (u32, usize, usize)> + '_ {
+    let mut pos = start;
+    std::iter::from_fn(move || {
+        if pos >= end {
+            return None;
+        }
+        let (id, size, data) = read_ebml_header(buffer, pos)?;
+        let element_end = match size {
+            Some(size) if size <= (end.saturating_sub(data)) as u64 => data + size as usize,
+            None if id == EBML_SEGMENT => end,
+            _ => return None,
+        };
+        pos = element_end;
+        Some((id, data, element_end))
+    })
+}
+
+fn read_ebml_uint(data: &[u8]) -> u64 {
+    data.iter()
+        .take(8)
+        .fold(0, |value, &byte| (value << 8) | byte as u64)
+}
+
+fn read_ebml_float(data: &[u8]) -> Option<f64> {
+    match data.len() {
+        4 => Some(f32::from_be_bytes(data.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(data.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+// AVI : hdrl/avih gives the duration and the dimensions, each hdrl/strl gives the kind (strh) and the codec (strf) of a stream
+fn avi_metadata(buffer: &[u8]) -> Result<VideoMetadata, StructureError> {
+    let (hdrl, hdrl_end) = riff_chunks(buffer, 12, buffer.len())
+        .find(|(id, data, _)| id == b"LIST" && buffer.get(*data..*data + 4) == Some(b"hdrl"))
+        .map(|(_, data, end)| (data + 4, end))
+        .ok_or(StructureError::MissingChunk("hdrl"))?;
+    let mut metadata = VideoMetadata {
+        duration: None,
+        dimensions: None,
+        tracks: Vec::new(),
+    };
+    for (id, data, end) in riff_chunks(buffer, hdrl, hdrl_end) {
+        if &id == b"avih" {
+            let avih = &buffer[data..end];
+            let frame_duration = read_u32_le(avih, 0).ok_or(StructureError::Truncated)?;
+            let frames = read_u32_le(avih, 16).ok_or(StructureError::Truncated)?;
+            metadata.duration = Some(Duration::from_micros(frame_duration as u64 * frames as u64));
+            metadata.dimensions = Some(Dimensions {
+                width: read_u32_le(avih, 32).ok_or(StructureError::Truncated)?,
+                height: read_u32_le(avih, 36).ok_or(StructureError::Truncated)?,
+            });
+        } else if &id == b"LIST" && buffer.get(data..data + 4) == Some(b"strl") {
+            let mut track = Track {
+                kind: TrackKind::Other,
+                codec: String::new(),
+            };
+            for (id, data, end) in riff_chunks(buffer, data + 4, end) {
+                match &id {
+                    b"strh" => {
+                        track.kind = match buffer.get(data..data + 4) {
+                            Some(b"vids") => TrackKind::Video,
+                            Some(b"auds") => TrackKind::Audio,
+                            _ => TrackKind::Other,
+                        };
+                        if track.codec.is_empty() {
+                            track.codec = buffer
+                                .get(data + 4..data + 8)
+                                .map(fourcc)
+                                .unwrap_or_default();
+                        }
+                    }
+                    // the format of the stream is more precise than the handler of the header
+                    b"strf" => {
+                        let strf = &buffer[data..end];
+                        let codec = match track.kind {
+                            TrackKind::Video => strf.get(16..20).map(fourcc),
+                            TrackKind::Audio => read_u16_le(strf, 0).map(audio_format_name),
+                            TrackKind::Other => None,
+                        };
+                        if let Some(codec) = codec.filter(|codec| !codec.is_empty()) {
+                            track.codec = codec;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            metadata.tracks.push(track);
+        }
+    }
+    Ok(metadata)
+}
+
+// iterate over the RIFF chunks between start and end, as (fourcc, offset of the data, end of the data)
+fn riff_chunks(
+    buffer: &[u8],
+    start: usize,
+    end: usize,
+) -> impl Iterator<Item = ([u8; 4], usize, usize)> + '_ {
+    let mut pos = start;
+    std::iter::from_fn(move || {
+        let id: [u8; 4] = buffer.get(pos..pos + 4)?.try_into().ok()?;
+        let size = read_u32_le(buffer, pos + 4)? as usize;
+        let data = pos + 8;
+        if data + size > end {
+            return None;
+        }
+        // the chunks are padded to an even size
+        pos = data + size + (size & 1);
+        Some((id, data, data + size))
+    })
+}
+
+// WMV : the File Properties object of the ASF header gives the duration, each Stream Properties object gives
+// the kind, the codec and for a video stream the dimensions
+fn asf_metadata(buffer: &[u8]) -> Result<VideoMetadata, StructureError> {
+    if buffer.get(0..16) != Some(&ASF_HEADER) {
+        return Err(StructureError::InvalidSignature);
+    }
+    let header_end = read_u64_le(buffer, 16).ok_or(StructureError::Truncated)?;
+    if header_end > buffer.len() as u64 {
+        return Err(StructureError::Truncated);
+    }
+    let mut metadata = VideoMetadata {
+        duration: None,
+        dimensions: None,
+        tracks: Vec::new(),
+    };
+    // the header object starts with its object count (4 bytes) and 2 reserved bytes
+    let mut pos = 30;
+    while pos + 24 <= header_end as usize {
+        let size = read_u64_le(buffer, pos + 16).ok_or(StructureError::Truncated)?;
+        if size < 24 || size > header_end - pos as u64 {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+        let object = &buffer[pos..pos + size as usize];
+        if object[0..16] == ASF_FILE_PROPERTIES {
+            // play duration in 100 ns units, including the preroll given in ms
+            let play_duration = read_u64_le(object, 64).ok_or(StructureError::Truncated)?;
+            let preroll = read_u64_le(object, 80).ok_or(StructureError::Truncated)?;
+            metadata.duration = Some(
+                Duration::from_nanos(play_duration.saturating_mul(100))
+                    .saturating_sub(Duration::from_millis(preroll)),
+            );
+        } else if object[0..16] == ASF_STREAM_PROPERTIES {
+            let stream_type = object.get(24..40).ok_or(StructureError::Truncated)?;
+            // type specific data : WAVEFORMATEX for audio, dimensions and BITMAPINFOHEADER for video
+            let specific = object.get(78..).ok_or(StructureError::Truncated)?;
+            if stream_type == ASF_VIDEO_MEDIA {
+                if metadata.dimensions.is_none() {
+                    metadata.dimensions = Some(Dimensions {
+                        width: read_u32_le(specific, 0).ok_or(StructureError::Truncated)?,
+                        height: read_u32_le(specific, 4).ok_or(StructureError::Truncated)?,
+                    });
+                }
+                metadata.tracks.push(Track {
+                    kind: TrackKind::Video,
+                    codec: specific.get(27..31).map(fourcc).unwrap_or_default(),
+                });
+            } else if stream_type == ASF_AUDIO_MEDIA {
+                metadata.tracks.push(Track {
+                    kind: TrackKind::Audio,
+                    codec: read_u16_le(specific, 0)
+                        .map(audio_format_name)
+                        .unwrap_or_default(),
+                });
+            } else {
+                metadata.tracks.push(Track {
+                    kind: TrackKind::Other,
+                    codec: String::new(),
+                });
+            }
+        }
+        pos += size as usize;
+    }
+    Ok(metadata)
+}
+
+// a fourcc without its padding spaces and null bytes
+fn fourcc(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches([' ', '\0'])
+        .to_string()
+}
+
+// name of the most common WAVEFORMATEX format tags, the hexadecimal value of the tag otherwise
+fn audio_format_name(tag: u16) -> String {
+    match tag {
+        0x0001 => "pcm".to_string(),
+        0x0003 => "pcm_float".to_string(),
+        0x0055 => "mp3".to_string(),
+        0x00FF | 0x1610 => "aac".to_string(),
+        0x0160 => "wmav1".to_string(),
+        0x0161 => "wmav2".to_string(),
+        0x0162 => "wmapro".to_string(),
+        0x2000 => "ac3".to_string(),
+        tag => format!("0x{:04x}", tag),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{video_metadata, Dimensions, StructureError, Track, TrackKind, VideoMetadata};
+    use std::fs;
+    use std::time::Duration;
+
+    fn metadata_of(path: &str) -> VideoMetadata {
+        let buffer = fs::read(path).unwrap();
+        video_metadata(&buffer, infer::get(&buffer).unwrap().mime_type())
+            .unwrap()
+            .unwrap()
+    }
+
+    fn tracks(video: &str, audio: &str) -> Vec<Track> {
+        vec![
+            Track {
+                kind: TrackKind::Video,
+                codec: video.to_string(),
+            },
+            Track {
+                kind: TrackKind::Audio,
+                codec: audio.to_string(),
+            },
+        ]
+    }
+
+    const DIMENSIONS: Option<Dimensions> = Some(Dimensions {
+        width: 480,
+        height: 270,
+    });
+
+    #[test]
+    fn mp4_metadata() {
+        assert_eq!(
+            metadata_of("res/video/file_example_MP4_480_1_5MG.mp4"),
+            VideoMetadata {
+                duration: Some(Duration::from_nanos(30_526_666_666)),
+                dimensions: DIMENSIONS,
+                tracks: tracks("avc1", "mp4a"),
+            }
+        );
+    }
+
+    #[test]
+    fn mov_metadata() {
+        assert_eq!(
+            metadata_of("res/video/file_example_MOV_480_700kB.mov"),
+            VideoMetadata {
+                duration: Some(Duration::from_millis(30_571)),
+                dimensions: DIMENSIONS,
+                tracks: tracks("avc1", "mp4a"),
+            }
+        );
+    }
+
+    #[test]
+    fn webm_metadata() {
+        assert_eq!(
+            metadata_of("res/video/file_example_WEBM_480_900KB.webm"),
+            VideoMetadata {
+                duration: Some(Duration::from_millis(30_543)),
+                dimensions: DIMENSIONS,
+                tracks: tracks("V_VP8", "A_VORBIS"),
+            }
+        );
+    }
+
+    #[test]
+    fn avi_metadata() {
+        assert_eq!(
+            metadata_of("res/video/file_example_AVI_480_750kB.avi"),
+            VideoMetadata {
+                duration: Some(Duration::from_micros(901 * 33_333)),
+                dimensions: DIMENSIONS,
+                tracks: tracks("H264", "aac"),
+            }
+        );
+    }
+
+    #[test]
+    fn wmv_metadata() {
+        assert_eq!(
+            metadata_of("res/video/file_example_WMV_480_1_2MB.wmv"),
+            VideoMetadata {
+                duration: Some(Duration::from_millis(30_550)),
+                dimensions: DIMENSIONS,
+                tracks: tracks("WMV2", "wmav2"),
+            }
+        );
+    }
+
+    #[test]
+    fn mvhd_version_1() {
+        let mp4 = |timescale: u32, duration: u64| {
+            let mut mvhd = vec![0, 0, 0, 0x78, b'm', b'v', b'h', b'd', 1, 0, 0, 0];
+            mvhd.extend_from_slice(&[0; 16]); // creation and modification times
+            mvhd.extend_from_slice(&timescale.to_be_bytes());
+            mvhd.extend_from_slice(&duration.to_be_bytes());
+            mvhd.resize(0x78, 0);
+            let mut moov = (8 + mvhd.len() as u32).to_be_bytes().to_vec();
+            moov.extend_from_slice(b"moov");
+            moov.extend_from_slice(&mvhd);
+            video_metadata(&moov, "video/mp4")
+                .unwrap()
+                .unwrap()
+                .duration
+        };
+        assert_eq!(mp4(1000, 5000), Some(Duration::from_secs(5)));
+        assert_eq!(
+            mp4(1, u64::MAX - 1),
+            None,
+            "A duration too long for a Duration schould be unknown, not wrapped"
+        );
+    }
+
+    #[test]
+    fn invalid_containers() {
+        assert_eq!(
+            video_metadata(b"\0\0\0\x08ftyp", "video/mp4"),
+            Err(StructureError::MissingChunk("moov"))
+        );
+        assert_eq!(
+            video_metadata(b"RIFF\0\0\0\0AVI ", "video/x-msvideo"),
+            Err(StructureError::MissingChunk("hdrl"))
+        );
+        assert_eq!(video_metadata(b"FLV", "video/x-flv"), Ok(None));
+    }
+}
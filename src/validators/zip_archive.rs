@@ -0,0 +1,354 @@
+// Date      : 17.10.2026
+// Place     : HEIG-VD, Vaud, Switzerland
+// Objective : Read the central directory of a ZIP archive without extracting it, and recognize the Office Open XML documents.
+
+use crate::{read_u16_le, read_u32_le, read_u64_le, StructureError};
+
+const LOCAL_FILE_HEADER: &[u8] = b"PK\x03\x04";
+const CENTRAL_DIRECTORY_HEADER: &[u8] = b"PK\x01\x02";
+const END_OF_CENTRAL_DIRECTORY: &[u8] = b"PK\x05\x06";
+const ZIP64_END_OF_CENTRAL_DIRECTORY: &[u8] = b"PK\x06\x06";
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR: &[u8] = b"PK\x06\x07";
+// size of the end of central directory record without its comment
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+// size of a central directory header without its name, extra field and comment
+const CENTRAL_DIRECTORY_HEADER_SIZE: usize = 46;
+
+// An entry of the central directory of a ZIP archive. Nothing is extracted.
+// The sizes are the ones declared by the archive, they can be lies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipEntry {
+    // name of the entry, as stored in the archive (a path with '/' separators)
+    pub name: String,
+    // general purpose bit flags
+    pub flags: u16,
+    // 0 for stored, 8 for deflate
+    pub compression_method: u16,
+    pub crc32: u32,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    // offset of the local file header of the entry, from the start of the archive
+    pub local_header_offset: u64,
+}
+
+impl ZipEntry {
+    pub fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.flags & 1 != 0
+    }
+}
+
+// position and content of the end of central directory record
+struct EndOfCentralDirectory {
+    entries: u64,
+    directory_size: u64,
+    directory_offset: u64,
+    // offset of the first byte after the record and its comment
+    end: usize,
+}
+
+// Read every entry of the central directory of a ZIP archive, ZIP64 included.
+// Return a StructureError if the end of central directory can't be found or if the directory is inconsistent.
+pub fn zip_entries(buffer: &[u8]) -> Result<Vec<ZipEntry>, StructureError> {
+    let eocd = end_of_central_directory(buffer)?;
+    let directory_end = eocd
+        .directory_offset
+        .checked_add(eocd.directory_size)
+        .filter(|end| *end <= buffer.len() as u64)
+        .ok_or(StructureError::Truncated)? as usize;
+    let mut pos = eocd.directory_offset as usize;
+    let mut entries = Vec::new();
+    for _ in 0..eocd.entries {
+        // every header is at least 46 bytes long, an entry count bigger than the directory is a lie
+        if pos + CENTRAL_DIRECTORY_HEADER_SIZE > directory_end
+            || buffer.get(pos..pos + 4) != Some(CENTRAL_DIRECTORY_HEADER)
+        {
+            return Err(StructureError::InvalidSegment { offset: pos });
+        }
+        let read_u16 = |offset| read_u16_le(buffer, pos + offset).unwrap_or_default();
+        let read_u32 = |offset| read_u32_le(buffer, pos + offset).unwrap_or_default();
+        let name_length = read_u16(28) as usize;
+        let extra_length = read_u16(30) as usize;
+        let comment_length = read_u16(32) as usize;
+        let name_start = pos + CENTRAL_DIRECTORY_HEADER_SIZE;
+        let extra_start = name_start + name_length;
+        let next = extra_start + extra_length + comment_length;
+        if next > directory_end {
+            return Err(StructureError::Truncated);
+        }
+        let mut entry = ZipEntry {
+            name: String::from_utf8_lossy(&buffer[name_start..extra_start]).into_owned(),
+            flags: read_u16(8),
+            compression_method: read_u16(10),
+            crc32: read_u32(16),
+            compressed_size: read_u32(20) as u64,
+            uncompressed_size: read_u32(24) as u64,
+            local_header_offset: read_u32(42) as u64,
+        };
+        read_zip64_extra_field(&buffer[extra_start..extra_start + extra_length], &mut entry);
+        entries.push(entry);
+        pos = next;
+    }
+    Ok(entries)
+}
+
+// the 32 bits fields set to 0xFFFFFFFF are replaced by the 64 bits values of the ZIP64 extra field (id 0x0001), in this order
+fn read_zip64_extra_field(extra: &[u8], entry: &mut ZipEntry) {
+    let mut pos = 0;
+    while let (Some(id), Some(size)) = (read_u16_le(extra, pos), read_u16_le(extra, pos + 2)) {
+        let data_start = pos + 4;
+        pos = data_start + size as usize;
+        if id != 0x0001 {
+            continue;
+        }
+        let mut field = data_start;
+        for value in [
+            &mut entry.uncompressed_size,
+            &mut entry.compressed_size,
+            &mut entry.local_header_offset,
+        ] {
+            if *value == 0xFFFF_FFFF {
+                match read_u64_le(extra, field).filter(|_| field + 8 <= pos) {
+                    Some(value_64) => *value = value_64,
+                    None => return,
+                }
+                field += 8;
+            }
+        }
+        return;
+    }
+}
+
+// Return the offset of the end of the ZIP archive : the end of the comment of the end of central directory record.
+pub(crate) fn zip_end(buffer: &[u8]) -> Result<usize, StructureError> {
+    if !buffer.starts_with(LOCAL_FILE_HEADER) && !buffer.starts_with(END_OF_CENTRAL_DIRECTORY) {
+        return Err(StructureError::InvalidSignature);
+    }
+    Ok(end_of_central_directory(buffer)?.end)
+}
+
+// the record is at the end of the archive, followed by a comment of at most 65535 bytes.
+// It is searched from the end, the last record whose comment fits in the content is used.
+fn end_of_central_directory(buffer: &[u8]) -> Result<EndOfCentralDirectory, StructureError> {
+    let last = buffer
+        .len()
+        .checked_sub(END_OF_CENTRAL_DIRECTORY_SIZE)
+        .ok_or(StructureError::Truncated)?;
+    let first = last.saturating_sub(u16::MAX as usize);
+    let pos = (first..=last)
+        .rev()
+        .find(|pos| {
+            buffer[*pos..].starts_with(END_OF_CENTRAL_DIRECTORY)
+                && pos
+                    + END_OF_CENTRAL_DIRECTORY_SIZE
+                    + read_u16_le(buffer, pos + 20).unwrap_or(0) as usize
+                    <= buffer.len()
+        })
+        .ok_or(StructureError::MissingChunk("end of central directory"))?;
+    let read_u16 = |offset| read_u16_le(buffer, pos + offset).unwrap_or_default();
+    let read_u32 = |offset| read_u32_le(buffer, pos + offset).unwrap_or_default();
+    let mut eocd = EndOfCentralDirectory {
+        entries: read_u16(10) as u64,
+        directory_size: read_u32(12) as u64,
+        directory_offset: read_u32(16) as u64,
+        end: pos + END_OF_CENTRAL_DIRECTORY_SIZE + read_u16(20) as usize,
+    };
+    // ZIP64 : the locator precedes the record and gives the offset of the ZIP64 end of central directory record
+    if eocd.entries == 0xFFFF
+        || eocd.directory_size == 0xFFFF_FFFF
+        || eocd.directory_offset == 0xFFFF_FFFF
+    {
+        let locator = pos
+            .checked_sub(20)
+            .filter(|locator| {
+                buffer[*locator..].starts_with(ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR)
+            })
+            .ok_or(StructureError::MissingChunk(
+                "ZIP64 end of central directory locator",
+            ))?;
+        let record = read_u64_le(buffer, locator + 8).ok_or(StructureError::Truncated)? as usize;
+        if buffer.get(record..record.saturating_add(4)) != Some(ZIP64_END_OF_CENTRAL_DIRECTORY) {
+            return Err(StructureError::MissingChunk(
+                "ZIP64 end of central directory",
+            ));
+        }
+        eocd.entries = read_u64_le(buffer, record + 32).ok_or(StructureError::Truncated)?;
+        eocd.directory_size = read_u64_le(buffer, record + 40).ok_or(StructureError::Truncated)?;
+        eocd.directory_offset =
+            read_u64_le(buffer, record + 48).ok_or(StructureError::Truncated)?;
+    }
+    Ok(eocd)
+}
+
+// Office Open XML documents, which are ZIP archives with a given set of parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OoxmlDocument {
+    Docx,
+    Xlsx,
+    Pptx,
+}
+
+impl OoxmlDocument {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            OoxmlDocument::Docx => {
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+            }
+            OoxmlDocument::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+            OoxmlDocument::Pptx => {
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+            }
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OoxmlDocument::Docx => "docx",
+            OoxmlDocument::Xlsx => "xlsx",
+            OoxmlDocument::Pptx => "pptx",
+        }
+    }
+}
+
+// Recognize an Office Open XML document from the entries of its central directory, wherever they are in the archive.
+// The archive must contain "[Content_Types].xml" and the main part of the document
+// ("word/document.xml", "xl/workbook.xml" or "ppt/presentation.xml"). Return None for a plain ZIP archive.
+pub fn ooxml_document(entries: &[ZipEntry]) -> Option<OoxmlDocument> {
+    let contains = |name: &str| entries.iter().any(|entry| entry.name == name);
+    if !contains("[Content_Types].xml") {
+        return None;
+    }
+    [
+        ("word/document.xml", OoxmlDocument::Docx),
+        ("xl/workbook.xml", OoxmlDocument::Xlsx),
+        ("ppt/presentation.xml", OoxmlDocument::Pptx),
+    ]
+    .into_iter()
+    .find(|(main_part, _)| contains(main_part))
+    .map(|(_, document)| document)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{crc32, ooxml_document, zip_entries, OoxmlDocument, StructureError};
+    use std::fs;
+
+    // Build a ZIP archive with stored (not compressed) entries.
+    pub(crate) fn stored_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        let mut directory = Vec::new();
+        for (name, content) in entries {
+            let offset = archive.len() as u32;
+            let mut common = Vec::new();
+            common.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            common.extend_from_slice(&0u16.to_le_bytes()); // flags
+            common.extend_from_slice(&0u16.to_le_bytes()); // method
+            common.extend_from_slice(&0u32.to_le_bytes()); // time and date
+            common.extend_from_slice(&crc32(content).to_le_bytes());
+            common.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            common.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            common.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+            archive.extend_from_slice(b"PK\x03\x04");
+            archive.extend_from_slice(&common);
+            archive.extend_from_slice(name.as_bytes());
+            archive.extend_from_slice(content);
+            directory.extend_from_slice(b"PK\x01\x02");
+            directory.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            directory.extend_from_slice(&common);
+            directory.extend_from_slice(&[0; 6]); // comment length, disk, internal attributes
+            directory.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+            directory.extend_from_slice(&offset.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+        }
+        let directory_offset = archive.len() as u32;
+        archive.extend_from_slice(&directory);
+        archive.extend_from_slice(b"PK\x05\x06");
+        archive.extend_from_slice(&[0; 4]); // disks
+        archive.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        archive.extend_from_slice(&directory_offset.to_le_bytes());
+        archive.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        archive
+    }
+
+    #[test]
+    fn zip_fixture_entries() {
+        let entries = zip_entries(&fs::read("res/other_extension/zip_2MB.zip").unwrap()).unwrap();
+        assert!(!entries.is_empty());
+        assert!(entries.iter().all(|entry| !entry.is_encrypted()));
+        assert_eq!(ooxml_document(&entries), None);
+    }
+
+    #[test]
+    fn docx_fixture_entries() {
+        let entries =
+            zip_entries(&fs::read("res/other_extension/file-sample_100kB.docx").unwrap()).unwrap();
+        assert!(entries
+            .iter()
+            .any(|entry| entry.name == "word/document.xml"));
+        assert_eq!(ooxml_document(&entries), Some(OoxmlDocument::Docx));
+    }
+
+    #[test]
+    fn stored_entries() {
+        let archive = stored_zip(&[("dir/", b""), ("dir/hello.txt", b"hello")]);
+        let entries = zip_entries(&archive).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_dir());
+        assert_eq!(entries[1].name, "dir/hello.txt");
+        assert_eq!(entries[1].uncompressed_size, 5);
+        assert_eq!(entries[1].crc32, 0x3610_A686);
+    }
+
+    #[test]
+    fn ooxml_documents() {
+        for (main_part, document) in [
+            ("word/document.xml", OoxmlDocument::Docx),
+            ("xl/workbook.xml", OoxmlDocument::Xlsx),
+            ("ppt/presentation.xml", OoxmlDocument::Pptx),
+        ] {
+            let archive = stored_zip(&[
+                ("_rels/.rels", b""),
+                (main_part, b""),
+                ("[Content_Types].xml", b""),
+            ]);
+            assert_eq!(
+                ooxml_document(&zip_entries(&archive).unwrap()),
+                Some(document),
+                "The order of the entries schould not matter"
+            );
+        }
+        let archive = stored_zip(&[("word/document.xml", b"")]);
+        assert_eq!(
+            ooxml_document(&zip_entries(&archive).unwrap()),
+            None,
+            "A ZIP without [Content_Types].xml is not an OOXML document"
+        );
+    }
+
+    #[test]
+    fn invalid_archives() {
+        assert_eq!(zip_entries(b"PK\x03\x04"), Err(StructureError::Truncated));
+        assert_eq!(
+            zip_entries(&[0; 100]),
+            Err(StructureError::MissingChunk("end of central directory"))
+        );
+        let mut archive = stored_zip(&[("a.txt", b"a")]);
+        let entries_count = archive.len() - 12;
+        archive[entries_count..entries_count + 2].copy_from_slice(&1000u16.to_le_bytes());
+        assert!(
+            matches!(
+                zip_entries(&archive),
+                Err(StructureError::InvalidSegment { .. })
+            ),
+            "An entry count bigger than the central directory schould be refused"
+        );
+    }
+}